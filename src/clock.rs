@@ -0,0 +1,386 @@
+use crate::Measurements;
+
+/// A source of monotonic time, used to timestamp measurements.
+///
+/// Implementations are provided for:
+///
+/// - [`MonotonicCounter`], a millisecond counter advanced by the application
+///   (for example, from a timer interrupt),
+/// - `EmbassyClock`, using [`embassy-time`], if the `embassy-time` feature
+///   is enabled,
+/// - `StdClock`, using [`std::time::Instant`], if the `std` feature is
+///   enabled.
+///
+/// [`embassy-time`]: https://docs.rs/embassy-time
+/// [`std::time::Instant`]: https://doc.rust-lang.org/std/time/struct.Instant.html
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+}
+
+/// An optional [`Clock`], used by the drivers to track the execution time of
+/// commands.
+///
+/// This is implemented for every [`Clock`], and for `()`, which represents the
+/// absence of a clock. This trait is sealed, and cannot be implemented outside
+/// of this crate.
+pub trait OptionalClock: sealed::Sealed {
+    /// Returns the current time, or [`None`] if there is no clock.
+    fn try_now(&self) -> Option<Instant>;
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for () {}
+    impl<C: super::Clock> Sealed for C {}
+}
+
+/// A point in time, in milliseconds since an arbitrary epoch defined by a
+/// [`Clock`].
+///
+/// `Instant`s from different clocks are not comparable.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(transparent)]
+pub struct Instant(u64);
+
+/// A [`Measurements`] with the time at which the sensor produced it.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct TimestampedMeasurements {
+    /// The measurements read from the sensor.
+    pub measurements: Measurements,
+    /// The time at which the sensor reported that the measurements were
+    /// ready.
+    pub timestamp: Instant,
+}
+
+/// A [`Clock`] backed by a millisecond counter which is advanced by the
+/// application.
+///
+/// This is intended for platforms without another time source, where a timer
+/// interrupt or main loop may call [`advance_ms()`](Self::advance_ms). Since
+/// the counter is atomic, a `MonotonicCounter` may be stored in a `static`
+/// and shared between an interrupt handler and the driver.
+///
+/// The counter is 64 bits wide, and does not wrap. On targets without 64-bit
+/// atomics, it is extended from a 32-bit atomic counter.
+///
+/// Targets without atomic read-modify-write operations, such as
+/// `thumbv6m-none-eabi`, have no atomic counter to build on. There,
+/// `MonotonicCounter` is only available if the `critical-section` feature is
+/// enabled, and the counter is accessed inside a [critical section]. The
+/// application must then provide a critical section implementation.
+///
+/// [critical section]: https://docs.rs/critical-section
+#[cfg(any(target_has_atomic = "32", feature = "critical-section"))]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct MonotonicCounter {
+    #[cfg(target_has_atomic = "64")]
+    ms: core::sync::atomic::AtomicU64,
+    #[cfg(all(target_has_atomic = "32", not(target_has_atomic = "64")))]
+    ms: ExtendedCounter,
+    #[cfg(not(target_has_atomic = "32"))]
+    ms: CriticalSectionCounter,
+}
+
+/// A 64-bit millisecond counter built from 32-bit atomics.
+///
+/// `halves` counts the number of times `low` has crossed a multiple of 2³¹.
+/// Because `low` is advanced before `halves`, a reader may see a `halves`
+/// which is one behind `low`, but never more, as long as each advance crosses
+/// at most one multiple of 2³¹. [`load()`](Self::load) produces the same
+/// value in either case.
+#[cfg(all(target_has_atomic = "32", any(test, not(target_has_atomic = "64"))))]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+struct ExtendedCounter {
+    low: core::sync::atomic::AtomicU32,
+    halves: core::sync::atomic::AtomicU32,
+}
+
+/// A 64-bit millisecond counter protected by a critical section, for targets
+/// without atomic read-modify-write operations.
+#[cfg(all(feature = "critical-section", any(test, not(target_has_atomic = "32"))))]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+struct CriticalSectionCounter {
+    ms: critical_section::Mutex<core::cell::Cell<u64>>,
+}
+
+/// A [`Clock`] using the [`embassy-time`] global time driver.
+///
+/// [`embassy-time`]: https://docs.rs/embassy-time
+#[cfg(feature = "embassy-time")]
+#[derive(Copy, Clone, Default)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct EmbassyClock;
+
+/// A [`Clock`] using [`std::time::Instant`].
+///
+/// The epoch of the returned [`Instant`]s is the time at which the
+/// `StdClock` was created.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct StdClock {
+    epoch: std::time::Instant,
+}
+
+// === impl Clock ===
+
+impl<C: Clock + ?Sized> Clock for &C {
+    #[inline]
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+// === impl OptionalClock ===
+
+impl OptionalClock for () {
+    #[inline]
+    fn try_now(&self) -> Option<Instant> {
+        None
+    }
+}
+
+impl<C: Clock> OptionalClock for C {
+    #[inline]
+    fn try_now(&self) -> Option<Instant> {
+        Some(self.now())
+    }
+}
+
+// === impl Instant ===
+
+impl Instant {
+    /// Returns an `Instant` the provided number of milliseconds after the
+    /// clock's epoch.
+    #[inline]
+    #[must_use]
+    pub const fn from_millis(ms: u64) -> Self {
+        Self(ms)
+    }
+
+    /// Returns the number of milliseconds since the clock's epoch.
+    #[inline]
+    #[must_use]
+    pub const fn as_millis(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the number of milliseconds elapsed from `earlier` to `self`, or
+    /// zero if `earlier` is later than `self`.
+    #[inline]
+    #[must_use]
+    pub const fn millis_since(self, earlier: Self) -> u64 {
+        self.0.saturating_sub(earlier.0)
+    }
+
+    /// Returns the `Instant` `ms` milliseconds after `self`, saturating at the
+    /// maximum representable instant.
+    #[inline]
+    #[must_use]
+    pub const fn add_millis(self, ms: u64) -> Self {
+        Self(self.0.saturating_add(ms))
+    }
+}
+
+// === impl MonotonicCounter ===
+
+#[cfg(any(target_has_atomic = "32", feature = "critical-section"))]
+impl MonotonicCounter {
+    /// Returns a new `MonotonicCounter` starting at zero.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            #[cfg(target_has_atomic = "64")]
+            ms: core::sync::atomic::AtomicU64::new(0),
+            #[cfg(all(target_has_atomic = "32", not(target_has_atomic = "64")))]
+            ms: ExtendedCounter::new(),
+            #[cfg(not(target_has_atomic = "32"))]
+            ms: CriticalSectionCounter::new(),
+        }
+    }
+
+    /// Advances the counter by `ms` milliseconds.
+    pub fn advance_ms(&self, ms: u32) {
+        #[cfg(target_has_atomic = "64")]
+        self.ms
+            .fetch_add(u64::from(ms), core::sync::atomic::Ordering::Relaxed);
+        #[cfg(not(target_has_atomic = "64"))]
+        self.ms.add(ms);
+    }
+}
+
+#[cfg(any(target_has_atomic = "32", feature = "critical-section"))]
+impl Default for MonotonicCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(any(target_has_atomic = "32", feature = "critical-section"))]
+impl Clock for MonotonicCounter {
+    fn now(&self) -> Instant {
+        #[cfg(target_has_atomic = "64")]
+        let ms = self.ms.load(core::sync::atomic::Ordering::Relaxed);
+        #[cfg(not(target_has_atomic = "64"))]
+        let ms = self.ms.load();
+        Instant(ms)
+    }
+}
+
+// === impl ExtendedCounter ===
+
+#[cfg(all(target_has_atomic = "32", any(test, not(target_has_atomic = "64"))))]
+impl ExtendedCounter {
+    const HALF: u32 = 1 << 31;
+
+    const fn new() -> Self {
+        Self {
+            low: core::sync::atomic::AtomicU32::new(0),
+            halves: core::sync::atomic::AtomicU32::new(0),
+        }
+    }
+
+    fn add(&self, mut ms: u32) {
+        use core::sync::atomic::Ordering;
+        // Advance in steps of less than 2³¹, so that each step crosses at most
+        // one multiple of 2³¹.
+        while ms > 0 {
+            let step = ms.min(Self::HALF - 1);
+            ms -= step;
+            let old = self.low.fetch_add(step, Ordering::Relaxed);
+            if (old ^ old.wrapping_add(step)) & Self::HALF != 0 {
+                self.halves.fetch_add(1, Ordering::Release);
+            }
+        }
+    }
+
+    fn load(&self) -> u64 {
+        use core::sync::atomic::Ordering;
+        let halves = self.halves.load(Ordering::Acquire);
+        let low = self.low.load(Ordering::Relaxed);
+        // If `halves` is odd, `low` is in the upper half of its range, unless
+        // it has since wrapped, and `halves` has not been incremented yet.
+        // Flipping its top bit makes both cases continuous.
+        let low = low ^ ((halves & 1) << 31);
+        (u64::from(halves) << 31) + u64::from(low)
+    }
+}
+
+// === impl CriticalSectionCounter ===
+
+#[cfg(all(feature = "critical-section", any(test, not(target_has_atomic = "32"))))]
+impl CriticalSectionCounter {
+    const fn new() -> Self {
+        Self {
+            ms: critical_section::Mutex::new(core::cell::Cell::new(0)),
+        }
+    }
+
+    fn add(&self, ms: u32) {
+        critical_section::with(|cs| {
+            let counter = self.ms.borrow(cs);
+            counter.set(counter.get().saturating_add(u64::from(ms)));
+        });
+    }
+
+    fn load(&self) -> u64 {
+        critical_section::with(|cs| self.ms.borrow(cs).get())
+    }
+}
+
+// === impl EmbassyClock ===
+
+#[cfg(feature = "embassy-time")]
+impl Clock for EmbassyClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        Instant(embassy_time::Instant::now().as_millis())
+    }
+}
+
+// === impl StdClock ===
+
+#[cfg(feature = "std")]
+impl StdClock {
+    /// Returns a new `StdClock` whose epoch is the current time.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            epoch: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    fn now(&self) -> Instant {
+        let elapsed = self.epoch.elapsed().as_millis();
+        Instant(u64::try_from(elapsed).unwrap_or(u64::MAX))
+    }
+}
+
+#[cfg(all(test, target_has_atomic = "32"))]
+mod tests {
+    use super::*;
+    use core::sync::atomic::Ordering;
+
+    #[test]
+    fn monotonic_counter_does_not_wrap() {
+        let counter = MonotonicCounter::new();
+        counter.advance_ms(u32::MAX);
+        counter.advance_ms(10);
+        assert_eq!(counter.now().as_millis(), u64::from(u32::MAX) + 10);
+    }
+
+    #[test]
+    fn extended_counter_does_not_wrap() {
+        let counter = ExtendedCounter::new();
+        counter.add(u32::MAX);
+        assert_eq!(counter.load(), u64::from(u32::MAX));
+        counter.add(10);
+        assert_eq!(counter.load(), u64::from(u32::MAX) + 10);
+        for _ in 0..4 {
+            counter.add(u32::MAX);
+        }
+        assert_eq!(counter.load(), 5 * u64::from(u32::MAX) + 10);
+    }
+
+    #[test]
+    fn extended_counter_tolerates_lagging_halves() {
+        // `low` has been advanced across a multiple of 2³¹, but `halves` has
+        // not been incremented yet.
+        let counter = ExtendedCounter::new();
+        counter
+            .low
+            .store(ExtendedCounter::HALF + 5, Ordering::Relaxed);
+        assert_eq!(counter.load(), u64::from(ExtendedCounter::HALF) + 5);
+        counter.halves.store(1, Ordering::Relaxed);
+        assert_eq!(counter.load(), u64::from(ExtendedCounter::HALF) + 5);
+
+        // `low` has wrapped around.
+        counter.low.store(5, Ordering::Relaxed);
+        assert_eq!(counter.load(), (1 << 32) + 5);
+        counter.halves.store(2, Ordering::Relaxed);
+        assert_eq!(counter.load(), (1 << 32) + 5);
+    }
+
+    #[cfg(feature = "critical-section")]
+    #[test]
+    fn critical_section_counter_does_not_wrap() {
+        let counter = CriticalSectionCounter::new();
+        counter.add(u32::MAX);
+        counter.add(10);
+        assert_eq!(counter.load(), u64::from(u32::MAX) + 10);
+    }
+}
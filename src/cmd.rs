@@ -1,9 +1,15 @@
-use crate::msg::{self, Decode, Encode};
+use crate::{
+    diagnostics::CrcErrors,
+    msg::{self, Decode, Encode},
+};
 
 pub(crate) trait ReadCommand {
     const RSP_BUF: Self::RspBuf;
     type RspBuf: AsMut<[u8]> + AsRef<[u8]>;
     type Rsp: Decode<Buf = Self::RspBuf>;
+
+    /// Returns the counter of CRC errors for this command.
+    fn crc_errors(errors: &mut CrcErrors) -> &mut u32;
 }
 
 pub(crate) trait WriteCommand {
@@ -12,13 +18,15 @@ pub(crate) trait WriteCommand {
 }
 
 pub(crate) trait WriteDataCommand: WriteCommand {
-    type Data: Encode;
+    type Data: Encode<Buf = Self::DataBuf>;
+    const DATA_BUF: Self::DataBuf;
+    type DataBuf: AsMut<[u8]> + AsRef<[u8]>;
     const REQ_BUF: Self::ReqBuf;
     type ReqBuf: AsMut<[u8]> + AsRef<[u8]>;
 }
 
 macro_rules! define_read_commands {
-    ($(struct $name:ident<$rsp:ty>: $cmd:literal, $exec:literal ms, [$bytes:literal];)+) => {
+    ($(struct $name:ident<$rsp:ty>: $cmd:literal, $exec:literal ms, [$bytes:literal] => $crc_errors:ident;)+) => {
         $(
             pub(crate) struct $name;
 
@@ -31,6 +39,10 @@ macro_rules! define_read_commands {
                 const RSP_BUF: [u8; $bytes] = [0; $bytes];
                 type RspBuf = [u8; $bytes];
                 type Rsp = $rsp;
+
+                fn crc_errors(errors: &mut CrcErrors) -> &mut u32 {
+                    &mut errors.$crc_errors
+                }
             }
         )+
     };
@@ -50,18 +62,41 @@ macro_rules! define_write_commands {
 }
 
 define_read_commands! {
-    struct ReadDataReady<msg::DataReady>: 0x0202, 20 ms, [3];
-    struct ReadMeasurement<msg::Measurements>: 0x03C4, 20 ms, [24];
-    struct ReadRawSignals<msg::RawSignals>: 0x03D2, 20 ms, [12];
-    struct ReadProductName<msg::RawString>: 0xD014, 20 ms, [47];
-    struct ReadSerialNumber<msg::RawString>: 0xD033, 20 ms, [47];
-    struct WarmStartParameter<u16>: 0x60C6, 20 ms, [3];
+    struct ReadDataReady<msg::DataReady>: 0x0202, 20 ms, [3] => data_ready;
+    struct ReadMeasurement<msg::Measurements>: 0x03C4, 20 ms, [24] => measurements;
+    struct ReadRawSignals<msg::RawSignals>: 0x03D2, 20 ms, [12] => raw_signals;
+    struct ReadProductName<msg::RawString>: 0xD014, 20 ms, [48] => product_name;
+    struct ReadSerialNumber<msg::RawString>: 0xD033, 20 ms, [48] => serial_number;
+    struct WarmStartParameter<u16>: 0x60C6, 20 ms, [3] => warm_start_parameter;
+    struct ReadDeviceStatus<msg::SensorStatus>: 0xD206, 20 ms, [6] => device_status;
+    struct TemperatureOffset<msg::TemperatureOffset>: 0x60B2, 20 ms, [9] => temperature_offset;
+    struct VocTuning<msg::AlgorithmTuning>: 0x60D0, 20 ms, [18] => voc_tuning;
+    struct NoxTuning<msg::AlgorithmTuning>: 0x60E1, 20 ms, [18] => nox_tuning;
+    struct RhtAccelerationMode<msg::RhtAccelerationMode>: 0x60F7, 20 ms, [3] => rht_acceleration_mode;
+    struct AutoCleaningInterval<u32>: 0x8004, 20 ms, [6] => auto_cleaning_interval;
+}
+
+macro_rules! impl_write_data_commands {
+    ($($name:ident<$data:ty>: [$bytes:literal];)+) => {
+        $(
+            impl WriteDataCommand for $name {
+                type Data = $data;
+                const DATA_BUF: Self::DataBuf = [0; $bytes];
+                type DataBuf = [u8; $bytes];
+                const REQ_BUF: Self::ReqBuf = [0; $bytes + 2];
+                type ReqBuf = [u8; $bytes + 2];
+            }
+        )+
+    };
 }
 
-impl WriteDataCommand for WarmStartParameter {
-    type Data = u16;
-    const REQ_BUF: Self::ReqBuf = [0; 5];
-    type ReqBuf = [u8; 5];
+impl_write_data_commands! {
+    WarmStartParameter<u16>: [3];
+    TemperatureOffset<msg::TemperatureOffset>: [9];
+    VocTuning<msg::AlgorithmTuning>: [18];
+    NoxTuning<msg::AlgorithmTuning>: [18];
+    RhtAccelerationMode<msg::RhtAccelerationMode>: [3];
+    AutoCleaningInterval<u32>: [6];
 }
 
 define_write_commands! {
@@ -0,0 +1,362 @@
+use crate::{CompensatedCelsius, CompensatedPercent, Measurements, RawSignals};
+
+/// Host-side compensation for self-heating of the sensor's temperature and
+/// humidity readings.
+///
+/// The SEN5x provides an on-sensor temperature offset, but this cannot account
+/// for heat sources whose output varies over time, such as a microcontroller
+/// whose power draw depends on its workload. A `Compensator` applies a
+/// [`ThermalModel`] to temperature readings on the host, and re-derives the
+/// relative humidity for the corrected temperature.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct Compensator {
+    model: ThermalModel,
+    /// The low-pass filtered heat source value, or [`None`] if no samples have
+    /// been processed yet.
+    heat: Option<f32>,
+}
+
+/// A first-order thermal model describing how much warmer the sensor reads
+/// than the ambient air.
+///
+/// The modeled temperature error is:
+///
+/// ```text
+/// error = offset_c + gain_c * heat_filtered
+/// ```
+///
+/// where `heat_filtered` is the heat source input passed through a first-order
+/// low-pass filter with the time constant `time_constant_s`. If the time
+/// constant is zero, the heat source is applied immediately, and the model is
+/// purely linear.
+///
+/// The heat source input may be in any unit (e.g. watts, milliamps, or a duty
+/// cycle), as long as `gain_c` is expressed in degrees Celsius per that unit.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct ThermalModel {
+    /// A constant temperature error in degrees Celsius, independent of the heat
+    /// source.
+    pub offset_c: f32,
+    /// The steady-state temperature error in degrees Celsius per unit of heat
+    /// source input.
+    pub gain_c: f32,
+    /// The thermal time constant, in seconds, of the sensor's response to the
+    /// heat source.
+    pub time_constant_s: f32,
+}
+
+/// A value with compensated temperature and humidity readings.
+///
+/// This is returned by [`Compensator::compensate`] and
+/// [`Compensator::compensate_raw`], and wraps the original, uncompensated
+/// [`Measurements`] or [`RawSignals`].
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct Compensated<T> {
+    inner: T,
+    temp: Option<CompensatedCelsius>,
+    rh: Option<CompensatedPercent>,
+}
+
+// Magnus formula coefficients for saturation vapor pressure over water, as used
+// by Sensirion.
+const MAGNUS_B: f32 = 17.62;
+const MAGNUS_C: f32 = 243.12;
+
+// === impl Compensator ===
+
+impl Compensator {
+    /// Returns a new `Compensator` using the provided [`ThermalModel`].
+    #[must_use]
+    pub const fn new(model: ThermalModel) -> Self {
+        Self { model, heat: None }
+    }
+
+    /// Returns the [`ThermalModel`] used by this compensator.
+    #[must_use]
+    pub const fn model(&self) -> &ThermalModel {
+        &self.model
+    }
+
+    /// Resets the state of the thermal model's low-pass filter.
+    ///
+    /// The next sample will be treated as if the heat source had been at its
+    /// current level indefinitely.
+    pub fn reset(&mut self) {
+        self.heat = None;
+    }
+
+    /// Compensates the temperature and humidity in a [`Measurements`].
+    ///
+    /// # Arguments
+    ///
+    /// - `measurements`: the measurements to compensate.
+    /// - `heat`: the current level of the heat source.
+    /// - `dt_s`: the time in seconds since the previous sample was
+    ///   compensated. The SEN5x produces a new measurement once per second.
+    pub fn compensate(
+        &mut self,
+        measurements: Measurements,
+        heat: f32,
+        dt_s: f32,
+    ) -> Compensated<Measurements> {
+        let (temp, rh) = self.correct(
+            measurements.temp_c(),
+            measurements.relative_humidity(),
+            heat,
+            dt_s,
+        );
+        Compensated {
+            inner: measurements,
+            temp,
+            rh,
+        }
+    }
+
+    /// Compensates the raw temperature and humidity signals in a
+    /// [`RawSignals`].
+    ///
+    /// The raw signals do not include the on-sensor temperature offset, so this
+    /// should be used with a [`ThermalModel`] that accounts for all of the
+    /// self-heating of the device.
+    ///
+    /// See [`compensate`](Self::compensate) for details on the arguments.
+    pub fn compensate_raw(
+        &mut self,
+        signals: RawSignals,
+        heat: f32,
+        dt_s: f32,
+    ) -> Compensated<RawSignals> {
+        let (temp, rh) = self.correct(
+            signals.raw_temp_c(),
+            signals.raw_relative_humidity(),
+            heat,
+            dt_s,
+        );
+        Compensated {
+            inner: signals,
+            temp,
+            rh,
+        }
+    }
+
+    /// Advances the thermal model by one sample, returning the current
+    /// temperature error in degrees Celsius.
+    fn update(&mut self, heat: f32, dt_s: f32) -> f32 {
+        let tau = self.model.time_constant_s;
+        let filtered = match self.heat {
+            Some(prev) if tau > 0.0 => {
+                let alpha = 1.0 - libm::expf(-dt_s.max(0.0) / tau);
+                prev + (heat - prev) * alpha
+            }
+            _ => heat,
+        };
+        self.heat = Some(filtered);
+        self.model.offset_c + self.model.gain_c * filtered
+    }
+
+    fn correct(
+        &mut self,
+        temp_c: Option<f32>,
+        rh: Option<f32>,
+        heat: f32,
+        dt_s: f32,
+    ) -> (Option<CompensatedCelsius>, Option<CompensatedPercent>) {
+        let error = self.update(heat, dt_s);
+        let Some(measured) = temp_c else {
+            // Without a temperature reading, there's nothing to compensate the
+            // humidity against.
+            return (None, None);
+        };
+        let corrected = measured - error;
+        let rh = rh.map(|rh| {
+            let ratio = saturation_exponent(measured) - saturation_exponent(corrected);
+            CompensatedPercent((rh * libm::expf(ratio)).clamp(0.0, 100.0))
+        });
+        (Some(CompensatedCelsius(corrected)), rh)
+    }
+}
+
+/// Returns the exponent of the Magnus formula for the given temperature.
+///
+/// The ratio of the saturation vapor pressures at two temperatures is
+/// `exp(saturation_exponent(t1) - saturation_exponent(t2))`.
+fn saturation_exponent(temp_c: f32) -> f32 {
+    MAGNUS_B * temp_c / (MAGNUS_C + temp_c)
+}
+
+// === impl ThermalModel ===
+
+impl ThermalModel {
+    /// A thermal model that performs no compensation.
+    pub const NONE: Self = Self::linear(0.0, 0.0);
+
+    /// Returns a linear thermal model with the provided constant offset and
+    /// gain, in degrees Celsius and degrees Celsius per unit of heat source
+    /// input, respectively.
+    ///
+    /// The returned model responds immediately to changes in the heat source.
+    /// Use [`with_time_constant_s`](Self::with_time_constant_s) to model
+    /// thermal lag.
+    #[must_use]
+    pub const fn linear(offset_c: f32, gain_c: f32) -> Self {
+        Self {
+            offset_c,
+            gain_c,
+            time_constant_s: 0.0,
+        }
+    }
+
+    /// Sets the thermal time constant of the model, in seconds.
+    #[inline]
+    #[must_use]
+    pub const fn with_time_constant_s(mut self, time_constant_s: f32) -> Self {
+        self.time_constant_s = time_constant_s;
+        self
+    }
+}
+
+impl Default for ThermalModel {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+// === impl Compensated ===
+
+impl<T> Compensated<T> {
+    /// Returns the compensated temperature, or [`None`] if no temperature
+    /// reading was present.
+    #[must_use]
+    pub fn temperature(&self) -> Option<CompensatedCelsius> {
+        self.temp
+    }
+
+    /// Returns the compensated relative humidity, or [`None`] if no humidity
+    /// reading was present.
+    ///
+    /// If no temperature reading was present, the humidity cannot be
+    /// compensated, and [`None`] is returned. The uncompensated humidity is
+    /// still available from [`uncompensated()`](Self::uncompensated).
+    #[must_use]
+    pub fn humidity(&self) -> Option<CompensatedPercent> {
+        self.rh
+    }
+
+    /// Returns a reference to the uncompensated value.
+    #[must_use]
+    pub fn uncompensated(&self) -> &T {
+        &self.inner
+    }
+
+    /// Consumes `self`, returning the uncompensated value.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(temp_c: f32, rh: f32) -> Measurements {
+        Measurements::builder()
+            .temp_c(temp_c)
+            .relative_humidity(rh)
+            .build()
+    }
+
+    fn temp(compensated: &Compensated<Measurements>) -> f32 {
+        compensated.temperature().unwrap().0
+    }
+
+    fn rh(compensated: &Compensated<Measurements>) -> f32 {
+        compensated.humidity().unwrap().0
+    }
+
+    #[test]
+    fn step_response() {
+        let model = ThermalModel::linear(1.0, 2.0).with_time_constant_s(10.0);
+        let mut compensator = Compensator::new(model);
+
+        // The first sample seeds the filter, as if the heat source had been
+        // off indefinitely.
+        let c = compensator.compensate(sample(25.0, 50.0), 0.0, 1.0);
+        assert!((temp(&c) - 24.0).abs() < 0.01);
+
+        // After one time constant, 63% of the step has been applied.
+        let mut c = c;
+        for _ in 0..10 {
+            c = compensator.compensate(sample(25.0, 50.0), 1.0, 1.0);
+        }
+        let expected = 25.0 - 1.0 - 2.0 * (1.0 - libm::expf(-1.0));
+        assert!((temp(&c) - expected).abs() < 0.01);
+
+        // The error converges to `offset_c + gain_c * heat`.
+        for _ in 0..200 {
+            c = compensator.compensate(sample(25.0, 50.0), 1.0, 1.0);
+        }
+        assert!((temp(&c) - 22.0).abs() < 0.01);
+
+        compensator.reset();
+        let c = compensator.compensate(sample(25.0, 50.0), 1.0, 1.0);
+        assert!((temp(&c) - 22.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn zero_dt_leaves_state_unchanged() {
+        let model = ThermalModel::linear(0.0, 2.0).with_time_constant_s(10.0);
+        let mut compensator = Compensator::new(model);
+        compensator.compensate(sample(25.0, 50.0), 0.0, 1.0);
+        let before = compensator.compensate(sample(25.0, 50.0), 1.0, 5.0);
+        let state = compensator.heat;
+
+        let after = compensator.compensate(sample(25.0, 50.0), 10.0, 0.0);
+        assert!(compensator.heat == state);
+        assert!(temp(&after) == temp(&before));
+
+        // A negative time step is treated as zero.
+        let after = compensator.compensate(sample(25.0, 50.0), 10.0, -1.0);
+        assert!(compensator.heat == state);
+        assert!(temp(&after) == temp(&before));
+    }
+
+    #[test]
+    fn humidity_keeps_dew_point() {
+        // 25 °C at 50 %RH and 20 °C at 67.74 %RH share a dew point of
+        // 13.85 °C.
+        let mut compensator = Compensator::new(ThermalModel::linear(5.0, 0.0));
+        let c = compensator.compensate(sample(25.0, 50.0), 0.0, 1.0);
+        assert!((temp(&c) - 20.0).abs() < 0.01);
+        assert!((rh(&c) - 67.74).abs() < 0.05);
+
+        // Heating the sensor less than the ambient air lowers the humidity.
+        let mut compensator = Compensator::new(ThermalModel::linear(-5.0, 0.0));
+        let c = compensator.compensate(sample(20.0, 67.74), 0.0, 1.0);
+        assert!((temp(&c) - 25.0).abs() < 0.01);
+        assert!((rh(&c) - 50.0).abs() < 0.05);
+
+        // Without a temperature, the humidity cannot be re-derived.
+        let c = compensator.compensate(
+            Measurements::builder().relative_humidity(50.0).build(),
+            0.0,
+            1.0,
+        );
+        assert!(c.temperature().is_none());
+        assert!(c.humidity().is_none());
+        assert!(c.uncompensated().relative_humidity() == Some(50.0));
+    }
+
+    #[test]
+    fn humidity_is_clamped() {
+        let mut compensator = Compensator::new(ThermalModel::linear(5.0, 0.0));
+        let c = compensator.compensate(sample(25.0, 90.0), 0.0, 1.0);
+        assert!(rh(&c) == 100.0);
+
+        let c = compensator.compensate(sample(25.0, -1.0), 0.0, 1.0);
+        assert!(rh(&c) == 0.0);
+    }
+}
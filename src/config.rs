@@ -0,0 +1,418 @@
+use crate::msg::{AlgorithmTuning, DecodeError, RhtAccelerationMode, TemperatureOffset};
+use crate::Fixed;
+use sensirion_i2c::crc8;
+
+/// A profile of the sensor's volatile configuration.
+///
+/// The sensor's configuration is lost when it is [reset] or loses power. A
+/// `Sen5xConfig` bundles every setting which must be written again afterwards,
+/// so that it can be stored (such as in flash, using
+/// [`to_bytes()`](Self::to_bytes)) and applied on every boot to restore
+/// identical behavior.
+///
+/// Settings which are [`None`] are left unchanged when the configuration is
+/// applied. A configuration read from the sensor has every setting present.
+///
+/// The configuration most recently applied through a driver is recorded in its
+/// [`State`](crate::State), and is written back to the sensor by the drivers'
+/// `reapply_config()` methods.
+///
+/// [reset]: crate::Sen5x::reset
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct Sen5xConfig {
+    pub(crate) warm_start: Option<u16>,
+    pub(crate) temperature_offset: Option<TemperatureOffset>,
+    pub(crate) voc_tuning: Option<AlgorithmTuning>,
+    pub(crate) nox_tuning: Option<AlgorithmTuning>,
+    pub(crate) rht_acceleration: Option<RhtAccelerationMode>,
+    pub(crate) auto_cleaning_interval_s: Option<u32>,
+}
+
+/// The current version of the serialized configuration format.
+const VERSION: u8 = 1;
+
+/// Bits in the serialized configuration indicating which settings are
+/// present.
+const WARM_START: u8 = 1 << 0;
+const TEMPERATURE_OFFSET: u8 = 1 << 1;
+const VOC_TUNING: u8 = 1 << 2;
+const NOX_TUNING: u8 = 1 << 3;
+const RHT_ACCELERATION: u8 = 1 << 4;
+const AUTO_CLEANING_INTERVAL: u8 = 1 << 5;
+/// Every bit used in the current version of the format.
+const ALL_SETTINGS: u8 = WARM_START
+    | TEMPERATURE_OFFSET
+    | VOC_TUNING
+    | NOX_TUNING
+    | RHT_ACCELERATION
+    | AUTO_CLEANING_INTERVAL;
+
+// === impl Sen5xConfig ===
+
+impl Sen5xConfig {
+    /// The length of a serialized configuration, in bytes.
+    ///
+    /// The serialized form consists of a format version byte, a byte
+    /// indicating which settings are present, each setting as big-endian
+    /// integers, and a CRC-8 checksum of the preceding bytes.
+    pub const SERIALIZED_LEN: usize = 41;
+
+    /// A configuration in which every setting is absent.
+    pub const EMPTY: Self = Self {
+        warm_start: None,
+        temperature_offset: None,
+        voc_tuning: None,
+        nox_tuning: None,
+        rht_acceleration: None,
+        auto_cleaning_interval_s: None,
+    };
+
+    /// Returns a new `Sen5xConfig` in which every setting is absent.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// Set the warm start parameter.
+    #[inline]
+    #[must_use]
+    pub const fn with_warm_start_parameter(mut self, param: u16) -> Self {
+        self.warm_start = Some(param);
+        self
+    }
+
+    /// Set the temperature offset parameters.
+    #[inline]
+    #[must_use]
+    pub const fn with_temperature_offset(mut self, offset: TemperatureOffset) -> Self {
+        self.temperature_offset = Some(offset);
+        self
+    }
+
+    /// Set the tuning parameters of the VOC index algorithm.
+    #[inline]
+    #[must_use]
+    pub const fn with_voc_tuning(mut self, tuning: AlgorithmTuning) -> Self {
+        self.voc_tuning = Some(tuning);
+        self
+    }
+
+    /// Set the tuning parameters of the NOx index algorithm.
+    #[inline]
+    #[must_use]
+    pub const fn with_nox_tuning(mut self, tuning: AlgorithmTuning) -> Self {
+        self.nox_tuning = Some(tuning);
+        self
+    }
+
+    /// Set the [`RhtAccelerationMode`].
+    #[inline]
+    #[must_use]
+    pub const fn with_rht_acceleration_mode(mut self, mode: RhtAccelerationMode) -> Self {
+        self.rht_acceleration = Some(mode);
+        self
+    }
+
+    /// Set the interval between automatic fan cleanings, in seconds.
+    ///
+    /// An interval of 0 disables automatic fan cleaning.
+    #[inline]
+    #[must_use]
+    pub const fn with_auto_cleaning_interval_s(mut self, interval_s: u32) -> Self {
+        self.auto_cleaning_interval_s = Some(interval_s);
+        self
+    }
+
+    /// Returns the warm start parameter, if present.
+    #[inline]
+    #[must_use]
+    pub const fn warm_start_parameter(&self) -> Option<u16> {
+        self.warm_start
+    }
+
+    /// Returns the temperature offset parameters, if present.
+    #[inline]
+    #[must_use]
+    pub const fn temperature_offset(&self) -> Option<TemperatureOffset> {
+        self.temperature_offset
+    }
+
+    /// Returns the tuning parameters of the VOC index algorithm, if present.
+    #[inline]
+    #[must_use]
+    pub const fn voc_tuning(&self) -> Option<AlgorithmTuning> {
+        self.voc_tuning
+    }
+
+    /// Returns the tuning parameters of the NOx index algorithm, if present.
+    #[inline]
+    #[must_use]
+    pub const fn nox_tuning(&self) -> Option<AlgorithmTuning> {
+        self.nox_tuning
+    }
+
+    /// Returns the [`RhtAccelerationMode`], if present.
+    #[inline]
+    #[must_use]
+    pub const fn rht_acceleration_mode(&self) -> Option<RhtAccelerationMode> {
+        self.rht_acceleration
+    }
+
+    /// Returns the interval between automatic fan cleanings in seconds, if
+    /// present.
+    #[inline]
+    #[must_use]
+    pub const fn auto_cleaning_interval_s(&self) -> Option<u32> {
+        self.auto_cleaning_interval_s
+    }
+
+    /// Returns `true` if applying this configuration requires the sensor to
+    /// be idle.
+    ///
+    /// The temperature offset and automatic cleaning interval may be set while
+    /// measuring, but every other setting requires the sensor to be idle.
+    #[must_use]
+    pub const fn requires_idle(&self) -> bool {
+        self.warm_start.is_some()
+            || self.voc_tuning.is_some()
+            || self.nox_tuning.is_some()
+            || self.rht_acceleration.is_some()
+    }
+
+    /// Returns a configuration with each setting present in `other` replacing
+    /// the corresponding setting in `self`.
+    #[must_use]
+    pub fn merge(self, other: &Self) -> Self {
+        Self {
+            warm_start: other.warm_start.or(self.warm_start),
+            temperature_offset: other.temperature_offset.or(self.temperature_offset),
+            voc_tuning: other.voc_tuning.or(self.voc_tuning),
+            nox_tuning: other.nox_tuning.or(self.nox_tuning),
+            rht_acceleration: other.rht_acceleration.or(self.rht_acceleration),
+            auto_cleaning_interval_s: other
+                .auto_cleaning_interval_s
+                .or(self.auto_cleaning_interval_s),
+        }
+    }
+
+    /// Serializes this configuration into a compact binary form, suitable for
+    /// storing in flash.
+    ///
+    /// The result may be deserialized using [`from_bytes()`](Self::from_bytes).
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_LEN] {
+        let mut buf = [0; Self::SERIALIZED_LEN];
+        let mut present = 0;
+        buf[0] = VERSION;
+        if let Some(param) = self.warm_start {
+            present |= WARM_START;
+            buf[2..4].copy_from_slice(&param.to_be_bytes());
+        }
+        if let Some(offset) = self.temperature_offset {
+            present |= TEMPERATURE_OFFSET;
+            buf[4..6].copy_from_slice(&offset.offset.raw().to_be_bytes());
+            buf[6..8].copy_from_slice(&offset.slope.raw().to_be_bytes());
+            buf[8..10].copy_from_slice(&offset.time_constant_s.to_be_bytes());
+        }
+        if let Some(tuning) = self.voc_tuning {
+            present |= VOC_TUNING;
+            put_tuning(&mut buf[10..22], &tuning);
+        }
+        if let Some(tuning) = self.nox_tuning {
+            present |= NOX_TUNING;
+            put_tuning(&mut buf[22..34], &tuning);
+        }
+        if let Some(mode) = self.rht_acceleration {
+            present |= RHT_ACCELERATION;
+            buf[34..36].copy_from_slice(&(mode as u16).to_be_bytes());
+        }
+        if let Some(interval_s) = self.auto_cleaning_interval_s {
+            present |= AUTO_CLEANING_INTERVAL;
+            buf[36..40].copy_from_slice(&interval_s.to_be_bytes());
+        }
+        buf[1] = present;
+        buf[40] = crc8::calculate(&buf[..40]);
+        buf
+    }
+
+    /// Deserializes a configuration produced by [`to_bytes()`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError::Crc`] if the checksum does not match, indicating
+    /// that the stored configuration is corrupt, or another [`DecodeError`] if
+    /// `bytes` has the wrong length, an unsupported format version, an unknown
+    /// setting, or an invalid setting.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let buf: &[u8; Self::SERIALIZED_LEN] = bytes
+            .try_into()
+            .map_err(|_| DecodeError::msg("serialized config has the wrong length"))?;
+        if crc8::calculate(&buf[..40]) != buf[40] {
+            return Err(DecodeError::Crc);
+        }
+        if buf[0] != VERSION {
+            return Err(DecodeError::msg("unsupported serialized config version"));
+        }
+        let present = buf[1];
+        if present & !ALL_SETTINGS != 0 {
+            return Err(DecodeError::msg("unknown setting in serialized config"));
+        }
+        let u16_at = |i: usize| u16::from_be_bytes([buf[i], buf[i + 1]]);
+        let i16_at = |i: usize| i16::from_be_bytes([buf[i], buf[i + 1]]);
+        let mut config = Self::EMPTY;
+        if present & WARM_START != 0 {
+            config.warm_start = Some(u16_at(2));
+        }
+        if present & TEMPERATURE_OFFSET != 0 {
+            config.temperature_offset = Some(TemperatureOffset {
+                offset: Fixed::from_raw(i16_at(4)),
+                slope: Fixed::from_raw(i16_at(6)),
+                time_constant_s: u16_at(8),
+            });
+        }
+        if present & VOC_TUNING != 0 {
+            config.voc_tuning = Some(get_tuning(&buf[10..22]));
+        }
+        if present & NOX_TUNING != 0 {
+            config.nox_tuning = Some(get_tuning(&buf[22..34]));
+        }
+        if present & RHT_ACCELERATION != 0 {
+            config.rht_acceleration = Some(RhtAccelerationMode::from_raw(u16_at(34))?);
+        }
+        if present & AUTO_CLEANING_INTERVAL != 0 {
+            config.auto_cleaning_interval_s =
+                Some(u32::from_be_bytes([buf[36], buf[37], buf[38], buf[39]]));
+        }
+        Ok(config)
+    }
+}
+
+impl Default for Sen5xConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn put_tuning(buf: &mut [u8], tuning: &AlgorithmTuning) {
+    let words = [
+        tuning.index_offset,
+        tuning.learning_time_offset_hours,
+        tuning.learning_time_gain_hours,
+        tuning.gating_max_duration_minutes,
+        tuning.std_initial,
+        tuning.gain_factor,
+    ];
+    for (chunk, word) in buf.chunks_exact_mut(2).zip(words) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+}
+
+fn get_tuning(buf: &[u8]) -> AlgorithmTuning {
+    let word = |i: usize| i16::from_be_bytes([buf[i * 2], buf[i * 2 + 1]]);
+    AlgorithmTuning {
+        index_offset: word(0),
+        learning_time_offset_hours: word(1),
+        learning_time_gain_hours: word(2),
+        gating_max_duration_minutes: word(3),
+        std_initial: word(4),
+        gain_factor: word(5),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL: Sen5xConfig = Sen5xConfig::new()
+        .with_warm_start_parameter(0x8000)
+        .with_temperature_offset(TemperatureOffset {
+            offset: Fixed::from_raw(-300),
+            slope: Fixed::from_raw(100),
+            time_constant_s: 10,
+        })
+        .with_voc_tuning(AlgorithmTuning::VOC_DEFAULT)
+        .with_nox_tuning(AlgorithmTuning::NOX_DEFAULT)
+        .with_rht_acceleration_mode(RhtAccelerationMode::Medium)
+        .with_auto_cleaning_interval_s(604_800);
+
+    #[test]
+    fn round_trip_all_settings() {
+        let bytes = FULL.to_bytes();
+        assert_eq!(bytes[..2], [VERSION, ALL_SETTINGS]);
+        assert_eq!(bytes[2..4], [0x80, 0x00]);
+        assert_eq!(bytes[4..6], [0xFE, 0xD4]);
+        assert_eq!(bytes[34..36], [0x00, 0x02]);
+        assert_eq!(bytes[36..40], [0x00, 0x09, 0x3A, 0x80]);
+        assert!(Sen5xConfig::from_bytes(&bytes).ok() == Some(FULL));
+    }
+
+    #[test]
+    fn round_trip_no_settings() {
+        let bytes = Sen5xConfig::EMPTY.to_bytes();
+        assert_eq!(bytes[..2], [VERSION, 0]);
+        assert!(bytes[2..40].iter().all(|&b| b == 0));
+        assert!(Sen5xConfig::from_bytes(&bytes).ok() == Some(Sen5xConfig::EMPTY));
+    }
+
+    #[test]
+    fn rejects_corruption() {
+        let bytes = FULL.to_bytes();
+        for i in 0..Sen5xConfig::SERIALIZED_LEN {
+            let mut corrupt = bytes;
+            corrupt[i] ^= 0x04;
+            assert!(matches!(
+                Sen5xConfig::from_bytes(&corrupt),
+                Err(DecodeError::Crc)
+            ));
+        }
+    }
+
+    /// Returns `bytes` with its checksum recomputed.
+    fn with_crc(mut bytes: [u8; Sen5xConfig::SERIALIZED_LEN]) -> [u8; Sen5xConfig::SERIALIZED_LEN] {
+        bytes[40] = crc8::calculate(&bytes[..40]);
+        bytes
+    }
+
+    #[test]
+    fn rejects_wrong_version() {
+        let mut bytes = FULL.to_bytes();
+        bytes[0] = VERSION + 1;
+        assert!(matches!(
+            Sen5xConfig::from_bytes(&with_crc(bytes)),
+            Err(DecodeError::Msg(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_wrong_length() {
+        let bytes = FULL.to_bytes();
+        assert!(Sen5xConfig::from_bytes(&bytes[..40]).is_err());
+        assert!(Sen5xConfig::from_bytes(&[]).is_err());
+        let mut long = [0; Sen5xConfig::SERIALIZED_LEN + 1];
+        long[..Sen5xConfig::SERIALIZED_LEN].copy_from_slice(&bytes);
+        assert!(Sen5xConfig::from_bytes(&long).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_settings() {
+        for bit in [1 << 6, 1 << 7] {
+            let mut bytes = FULL.to_bytes();
+            bytes[1] |= bit;
+            assert!(matches!(
+                Sen5xConfig::from_bytes(&with_crc(bytes)),
+                Err(DecodeError::Msg(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        let mut bytes = FULL.to_bytes();
+        bytes[35] = 3;
+        assert!(matches!(
+            Sen5xConfig::from_bytes(&with_crc(bytes)),
+            Err(DecodeError::Msg(_))
+        ));
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{DecodeError, Error, Instant, RetryStats};
+
+/// Counters tracking the health of a driver's communication with the sensor.
+///
+/// These are returned by the drivers' `diagnostics()` methods, and may be used
+/// to monitor the quality of the I²C bus to each sensor. All counters saturate
+/// rather than wrapping.
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[non_exhaustive]
+pub struct Diagnostics {
+    /// The number of commands written to the sensor, including commands which
+    /// failed and retries.
+    pub commands_sent: u32,
+    /// The number of I²C errors which occurred while writing commands.
+    pub write_errors: u32,
+    /// The number of I²C errors which occurred while reading responses.
+    pub read_errors: u32,
+    /// The number of responses which failed CRC8 validation, by command.
+    pub crc_errors: CrcErrors,
+    /// The number of responses which had valid checksums, but could not be
+    /// decoded.
+    pub decode_errors: u32,
+    /// The number of operations which failed because the sensor was in the
+    /// wrong [`Mode`](crate::Mode).
+    pub mode_errors: u32,
+    /// The number of times waiting for data timed out.
+    pub timeouts: u32,
+    /// The number of responses which were successfully read and decoded.
+    pub successful_reads: u32,
+    /// The number of consecutive read commands which have failed since the
+    /// last successful read.
+    pub consecutive_failures: u32,
+    /// Counters tracking how many retries were performed by the driver's
+    /// [`RetryPolicy`](crate::RetryPolicy).
+    pub retries: RetryStats,
+    /// The time at which a response was last successfully read, or [`None`]
+    /// if no response has been read since the driver was created or its
+    /// diagnostics were reset.
+    ///
+    /// This is only recorded if the driver has a [`Clock`](crate::Clock).
+    pub last_success: Option<Instant>,
+}
+
+/// Counters of responses which failed CRC8 validation, by command.
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[non_exhaustive]
+pub struct CrcErrors {
+    /// CRC errors reading the data-ready flag.
+    pub data_ready: u32,
+    /// CRC errors reading measurements.
+    pub measurements: u32,
+    /// CRC errors reading raw signals.
+    pub raw_signals: u32,
+    /// CRC errors reading the product name.
+    pub product_name: u32,
+    /// CRC errors reading the serial number.
+    pub serial_number: u32,
+    /// CRC errors reading the warm start parameter.
+    pub warm_start_parameter: u32,
+    /// CRC errors reading the device status register.
+    pub device_status: u32,
+    /// CRC errors reading the temperature offset parameters.
+    pub temperature_offset: u32,
+    /// CRC errors reading the VOC algorithm tuning parameters.
+    pub voc_tuning: u32,
+    /// CRC errors reading the NOx algorithm tuning parameters.
+    pub nox_tuning: u32,
+    /// CRC errors reading the RH/T acceleration mode.
+    pub rht_acceleration_mode: u32,
+    /// CRC errors reading the automatic fan cleaning interval.
+    pub auto_cleaning_interval: u32,
+}
+
+// === impl Diagnostics ===
+
+impl Diagnostics {
+    pub(crate) const fn new() -> Self {
+        Self {
+            commands_sent: 0,
+            write_errors: 0,
+            read_errors: 0,
+            crc_errors: CrcErrors {
+                data_ready: 0,
+                measurements: 0,
+                raw_signals: 0,
+                product_name: 0,
+                serial_number: 0,
+                warm_start_parameter: 0,
+                device_status: 0,
+                temperature_offset: 0,
+                voc_tuning: 0,
+                nox_tuning: 0,
+                rht_acceleration_mode: 0,
+                auto_cleaning_interval: 0,
+            },
+            decode_errors: 0,
+            mode_errors: 0,
+            timeouts: 0,
+            successful_reads: 0,
+            consecutive_failures: 0,
+            retries: RetryStats::new(),
+            last_success: None,
+        }
+    }
+
+    /// Records a command being written to the sensor.
+    pub(crate) fn command_sent(&mut self) {
+        incr(&mut self.commands_sent);
+    }
+
+    /// Records the outcome of writing a command to the sensor.
+    pub(crate) fn record_write<E>(&mut self, result: Result<(), E>) -> Result<(), Error<E>> {
+        self.command_sent();
+        result.map_err(|e| {
+            incr(&mut self.write_errors);
+            Error::I2cWrite(e)
+        })
+    }
+
+    /// Records the outcome of reading a response from the sensor.
+    pub(crate) fn record_read<E>(&mut self, result: Result<(), E>) -> Result<(), Error<E>> {
+        result.map_err(|e| {
+            incr(&mut self.read_errors);
+            Error::I2cRead(e)
+        })
+    }
+
+    /// Records the outcome of decoding a response.
+    ///
+    /// `crc_errors` selects the counter incremented if the response failed
+    /// CRC8 validation.
+    pub(crate) fn record_decode<T, E>(
+        &mut self,
+        result: Result<T, DecodeError>,
+        crc_errors: fn(&mut CrcErrors) -> &mut u32,
+    ) -> Result<T, Error<E>> {
+        result.map_err(|e| {
+            match e {
+                DecodeError::Crc => incr(crc_errors(&mut self.crc_errors)),
+                DecodeError::Msg(_) => incr(&mut self.decode_errors),
+            }
+            Error::Decode(e)
+        })
+    }
+
+    /// Records an operation failing because the sensor was in the wrong mode.
+    pub(crate) fn mode_error(&mut self) {
+        incr(&mut self.mode_errors);
+    }
+
+    /// Records a timeout waiting for data.
+    pub(crate) fn timeout(&mut self) {
+        incr(&mut self.timeouts);
+    }
+
+    /// Records the final outcome of a read command, after any retries.
+    ///
+    /// `max_attempts` is the number of attempts permitted by the retry policy,
+    /// and `now` is the current time, if the driver has a clock.
+    pub(crate) fn record_attempts<T, E>(
+        &mut self,
+        attempts: u8,
+        max_attempts: u8,
+        res: &Result<T, Error<E>>,
+        now: Option<Instant>,
+    ) where
+        E: embedded_hal::i2c::Error,
+    {
+        let succeeded = res.is_ok();
+        let exhausted = attempts == max_attempts && res.as_ref().is_err_and(|e| e.is_retriable());
+        self.retries.record(attempts, succeeded, exhausted);
+        if succeeded {
+            incr(&mut self.successful_reads);
+            self.last_success = now.or(self.last_success);
+            self.consecutive_failures = 0;
+        } else {
+            incr(&mut self.consecutive_failures);
+        }
+    }
+}
+
+// === impl CrcErrors ===
+
+impl CrcErrors {
+    /// Returns the total number of CRC errors across all commands.
+    #[must_use]
+    pub fn total(&self) -> u32 {
+        let Self {
+            data_ready,
+            measurements,
+            raw_signals,
+            product_name,
+            serial_number,
+            warm_start_parameter,
+            device_status,
+            temperature_offset,
+            voc_tuning,
+            nox_tuning,
+            rht_acceleration_mode,
+            auto_cleaning_interval,
+        } = *self;
+        [
+            data_ready,
+            measurements,
+            raw_signals,
+            product_name,
+            serial_number,
+            warm_start_parameter,
+            device_status,
+            temperature_offset,
+            voc_tuning,
+            nox_tuning,
+            rht_acceleration_mode,
+            auto_cleaning_interval,
+        ]
+        .into_iter()
+        .fold(0u32, u32::saturating_add)
+    }
+}
+
+fn incr(counter: &mut u32) {
+    *counter = counter.saturating_add(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal::i2c::ErrorKind;
+
+    #[test]
+    fn exhausted_only_by_retriable_errors() {
+        let mut diagnostics = Diagnostics::new();
+        let bus_error = Err::<(), _>(Error::I2cRead(ErrorKind::Bus));
+
+        diagnostics.record_attempts(3, 3, &bus_error, None);
+        assert_eq!(diagnostics.retries.exhausted, 1);
+
+        // A retry policy which never retries is exhausted by the first
+        // attempt.
+        diagnostics.record_attempts(1, 1, &bus_error, None);
+        assert_eq!(diagnostics.retries.exhausted, 2);
+
+        // Errors which are not retriable stop retrying early, and don't
+        // exhaust the retry policy.
+        diagnostics.record_attempts(1, 3, &Err::<(), _>(Error::<ErrorKind>::Timeout), None);
+        diagnostics.record_attempts(3, 3, &Err::<(), _>(Error::<ErrorKind>::Timeout), None);
+        assert_eq!(diagnostics.retries.exhausted, 2);
+        assert_eq!(diagnostics.consecutive_failures, 4);
+    }
+
+    #[test]
+    fn last_success() {
+        let mut diagnostics = Diagnostics::new();
+        let now = Instant::from_millis(1000);
+        diagnostics.record_attempts(1, 1, &Ok::<_, Error<ErrorKind>>(()), Some(now));
+        assert_eq!(diagnostics.last_success.map(Instant::as_millis), Some(1000));
+
+        let failure = Err::<(), _>(Error::I2cRead(ErrorKind::Bus));
+        diagnostics.record_attempts(1, 1, &failure, Some(now.add_millis(1000)));
+        assert_eq!(diagnostics.last_success.map(Instant::as_millis), Some(1000));
+
+        // Without a clock, the last known success is kept.
+        diagnostics.record_attempts(1, 1, &Ok::<_, Error<ErrorKind>>(()), None);
+        assert_eq!(diagnostics.last_success.map(Instant::as_millis), Some(1000));
+    }
+}
@@ -0,0 +1,1228 @@
+use core::borrow::BorrowMut;
+
+use crate::{
+    cmd::{self, ReadCommand, WriteCommand, WriteDataCommand},
+    fan_cleaning,
+    msg::{self, Decode, Encode},
+    power, Clock, CommandOutput, Diagnostics, Error, FanCleaningSchedule, Health, Mode,
+    OptionalClock, ParticulateMode, PendingCommand, RecoveryAction, RetryPolicy, RetryStats,
+    Sen5xConfig, SensorKind, State, Supervisor, TimestampedMeasurements,
+};
+use embedded_hal::{delay::DelayNs, digital::OutputPin, i2c::I2c};
+#[cfg(feature = "embedded-hal-async")]
+use embedded_hal_async::{delay::DelayNs as AsyncDelayNs, i2c::I2c as AsyncI2c};
+
+mod array;
+mod duty_cycle;
+mod measurements;
+
+pub use self::{array::Sen5xArray, duty_cycle::DutyCycleIter, measurements::MeasurementIter};
+#[cfg(feature = "embedded-hal-async")]
+pub use self::{
+    array::Sen5xArrayAsync, duty_cycle::DutyCycleStream, measurements::MeasurementStream,
+};
+
+/// A blocking driver for the SEN5x, using the [`embedded_hal`] I²C traits.
+///
+/// See [`Sen5xAsync`](crate::Sen5xAsync) for an asynchronous driver.
+///
+/// # Sharing the bus
+///
+/// The driver does not need to own the I²C peripheral. Any type implementing
+/// [`I2c`] may be used, including:
+///
+/// - `&mut I`, as [`I2c`] is implemented for mutable references. A driver
+///   may be constructed for each operation using
+///   [`from_parts()`](Self::from_parts), with the sensor's [`State`] stored
+///   separately, so that the bus is only borrowed while communicating with
+///   the sensor.
+/// - The shared bus devices provided by the [`embedded-hal-bus`] crate, such
+///   as `RefCellDevice` (for sharing a bus within a single thread) and
+///   `CriticalSectionDevice` (for sharing a bus between interrupt handlers or
+///   threads). Each device may be owned by its own driver.
+///
+/// [`embedded-hal-bus`]: https://docs.rs/embedded-hal-bus
+pub struct Sen5x<I, S = State, K = ()> {
+    i2c: I,
+    state: S,
+    clock: K,
+}
+
+/// An asynchronous driver for the SEN5x, using the [`embedded_hal_async`] I²C
+/// traits.
+///
+/// See [`Sen5x`](crate::Sen5x) for a blocking driver.
+///
+/// # Sharing the bus
+///
+/// The driver does not need to own the I²C peripheral. Any type implementing
+/// [`I2c`] may be used, including:
+///
+/// - `&mut I`, as [`I2c`] is implemented for mutable references. A driver
+///   may be constructed for each operation using
+///   [`from_parts()`](Self::from_parts), with the sensor's [`State`] stored
+///   separately, so that the bus is only borrowed while communicating with
+///   the sensor.
+/// - Devices which share a bus behind an async mutex, such as the `I2cDevice`
+///   type provided by [`embassy-embedded-hal`]. Each device may be owned by
+///   its own driver, and the bus is locked for the duration of each I²C
+///   transaction.
+///
+/// [`embassy-embedded-hal`]: https://docs.rs/embassy-embedded-hal
+#[cfg(feature = "embedded-hal-async")]
+pub struct Sen5xAsync<I, S = State, K = ()> {
+    i2c: I,
+    state: S,
+    clock: K,
+}
+
+#[maybe_async_cfg::maybe(
+    idents(
+        Sen5x(sync, async = "Sen5xAsync"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I> Sen5x<I> {
+    pub const fn new(i2c: I) -> Self {
+        Self::from_parts(i2c, State::new())
+    }
+
+    /// Set the I²C address of the sensor.
+    ///
+    /// The [`new()`](Self::new) constructor will use the sensor's default I²C
+    /// address (`0x69`). Use this method to set a different address, such as in
+    /// cases where an I²C multiplexer is in use.
+    #[inline]
+    #[must_use]
+    pub const fn with_i2c_address(mut self, addr: u8) -> Self {
+        self.state = self.state.with_i2c_address(addr);
+        self
+    }
+
+    /// Set the [`RetryPolicy`] used when reading from the sensor.
+    ///
+    /// By default, [`RetryPolicy::NONE`] is used, and failed reads are never
+    /// retried. When a retry policy is set, read commands which fail with a
+    /// [retriable error](Error::is_retriable) are re-sent, waiting for the
+    /// policy's backoff between attempts.
+    #[inline]
+    #[must_use]
+    pub const fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.state = self.state.with_retry_policy(retry);
+        self
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    idents(
+        Sen5x(sync, async = "Sen5xAsync"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, S> Sen5x<I, S> {
+    /// Returns a driver using the provided I²C bus and sensor [`State`].
+    ///
+    /// The driver does not need to own either: `I` may be a mutable reference
+    /// to a bus (or to a shared bus device, such as those provided by the
+    /// [`embedded-hal-bus`] crate), and `S` may be a mutable reference to a
+    /// [`State`] stored elsewhere. This allows a driver to be constructed for
+    /// each operation, so that the bus is only borrowed while communicating
+    /// with the sensor, and multiple sensors and other devices can share a
+    /// bus.
+    ///
+    /// [`embedded-hal-bus`]: https://docs.rs/embedded-hal-bus
+    #[inline]
+    #[must_use]
+    pub const fn from_parts(i2c: I, state: S) -> Self {
+        Self {
+            i2c,
+            state,
+            clock: (),
+        }
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    idents(
+        Sen5x(sync, async = "Sen5xAsync"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, S, K> Sen5x<I, S, K> {
+    /// Set a [`Clock`] used to track the execution time of commands.
+    ///
+    /// By default, the driver waits for a command's full execution time
+    /// immediately after sending it. With a clock, the driver instead records
+    /// the time at which the command was sent, and waits only for whatever
+    /// remains of its execution time before the next transaction with the
+    /// sensor. Time the application spends between commands, such as while
+    /// processing a measurement, is then not spent waiting again.
+    ///
+    /// The clock is also used to timestamp measurements read by
+    /// `measure_timestamped()`.
+    ///
+    /// The time is recorded in the sensor's [`State`], so the same clock should
+    /// be used for every driver constructed from that state.
+    #[inline]
+    #[must_use]
+    pub fn with_clock<C: Clock>(self, clock: C) -> Sen5x<I, S, C> {
+        Sen5x {
+            i2c: self.i2c,
+            state: self.state,
+            clock,
+        }
+    }
+
+    /// Consumes the driver, returning the I²C bus and the sensor [`State`].
+    #[inline]
+    #[must_use]
+    pub fn into_parts(self) -> (I, S) {
+        (self.i2c, self.state)
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    idents(
+        Sen5x(sync, async = "Sen5xAsync"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, S, K> Sen5x<I, S, K>
+where
+    S: BorrowMut<State>,
+{
+    /// Returns the sensor's [`State`].
+    #[inline]
+    #[must_use]
+    pub fn state(&self) -> &State {
+        self.state.borrow()
+    }
+
+    /// Returns counters tracking how many retries have been performed by the
+    /// driver's [`RetryPolicy`].
+    #[must_use]
+    pub fn retry_stats(&self) -> RetryStats {
+        self.state().diagnostics.retries
+    }
+
+    /// Returns [`Diagnostics`] counters tracking the health of the driver's
+    /// communication with the sensor.
+    #[must_use]
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.state().diagnostics
+    }
+
+    /// Resets all [`Diagnostics`] counters to zero, including the
+    /// [`retry_stats()`](Self::retry_stats).
+    pub fn reset_diagnostics(&mut self) {
+        self.state_mut().diagnostics = Diagnostics::new();
+    }
+
+    #[inline]
+    fn state_mut(&mut self) -> &mut State {
+        self.state.borrow_mut()
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    idents(
+        Sen5x(sync, async = "Sen5xAsync"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, S, K> Sen5x<I, S, K>
+where
+    I: I2c,
+    S: BorrowMut<State>,
+    K: OptionalClock,
+{
+    async fn read_command<C>(&mut self, delay: &mut impl DelayNs) -> Result<C::Rsp, Error<I::Error>>
+    where
+        C: WriteCommand + ReadCommand,
+    {
+        let max_attempts = self.state().retry.max_attempts();
+        let mut attempts = 1;
+        loop {
+            match self.read_command_once::<C>(delay).await {
+                Err(e) if e.is_retriable() && attempts < max_attempts => {
+                    delay
+                        .delay_ms(self.state().retry.backoff_ms(attempts))
+                        .await;
+                    attempts += 1;
+                }
+                res => {
+                    let now = self.clock.try_now();
+                    self.state_mut()
+                        .diagnostics
+                        .record_attempts(attempts, max_attempts, &res, now);
+                    return res;
+                }
+            }
+        }
+    }
+
+    async fn read_command_once<C>(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<C::Rsp, Error<I::Error>>
+    where
+        C: WriteCommand + ReadCommand,
+    {
+        self.write_command::<C>(delay).await?;
+        self.wait_for_execution(delay).await;
+        let mut buf = C::RSP_BUF;
+        let res = self.i2c.read(self.state().addr, buf.as_mut()).await;
+        self.state_mut().diagnostics.record_read(res)?;
+        self.state_mut()
+            .diagnostics
+            .record_decode(C::Rsp::decode(&buf), C::crc_errors)
+    }
+
+    async fn write_command<C>(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<I::Error>>
+    where
+        C: WriteCommand,
+    {
+        self.wait_for_execution(delay).await;
+        let res = self.i2c.write(self.state().addr, &C::COMMAND).await;
+        self.state_mut().diagnostics.record_write(res)?;
+        self.executing(C::EXECUTION_MS, delay).await;
+        Ok(())
+    }
+
+    async fn write_data_command<C>(
+        &mut self,
+        delay: &mut impl DelayNs,
+        data: C::Data,
+    ) -> Result<(), Error<I::Error>>
+    where
+        C: WriteDataCommand,
+    {
+        let mut data_buf = C::DATA_BUF;
+        data.encode(&mut data_buf);
+        let mut buf = C::REQ_BUF;
+        {
+            let buf = buf.as_mut();
+            buf[..2].copy_from_slice(&C::COMMAND);
+            buf[2..].copy_from_slice(data_buf.as_ref());
+        };
+        self.wait_for_execution(delay).await;
+        let res = self.i2c.write(self.state().addr, buf.as_ref()).await;
+        self.state_mut().diagnostics.record_write(res)?;
+        self.executing(C::EXECUTION_MS, delay).await;
+        Ok(())
+    }
+
+    /// Records that a command taking `execution_ms` to execute was sent.
+    ///
+    /// Without a clock, this waits for the command to execute. With a clock,
+    /// the time is recorded, and the wait is deferred until the next
+    /// transaction.
+    async fn executing(&mut self, execution_ms: usize, delay: &mut impl DelayNs) {
+        match self.clock.try_now() {
+            Some(now) => self.state_mut().executing = Some((now, execution_ms as u32)),
+            None => delay.delay_ms(execution_ms as u32).await,
+        }
+    }
+
+    /// Waits for whatever remains of the execution time of the last command
+    /// recorded by [`executing()`](Self::executing).
+    ///
+    /// If the driver has no clock, the full execution time is waited.
+    async fn wait_for_execution(&mut self, delay: &mut impl DelayNs) {
+        let Some((sent_at, execution_ms)) = self.state_mut().executing.take() else {
+            return;
+        };
+        let elapsed_ms = self
+            .clock
+            .try_now()
+            .map_or(0, |now| now.millis_since(sent_at));
+        let remaining_ms = u64::from(execution_ms).saturating_sub(elapsed_ms);
+        if remaining_ms > 0 {
+            delay.delay_ms(remaining_ms as u32).await;
+        }
+    }
+
+    pub async fn data_ready(&mut self, delay: &mut impl DelayNs) -> Result<bool, Error<I::Error>> {
+        let msg::DataReady(ready) = self.read_command::<cmd::ReadDataReady>(delay).await?;
+        self.state_mut().data_ready(ready);
+        Ok(ready)
+    }
+
+    pub async fn start_measurement(
+        &mut self,
+        particulates: ParticulateMode,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I::Error>> {
+        match particulates {
+            ParticulateMode::Enabled => {
+                self.write_command::<cmd::StartMeasurement>(delay).await?;
+            }
+            ParticulateMode::Disabled => {
+                self.write_command::<cmd::StartMeasurementNoParticulates>(delay)
+                    .await?;
+            }
+        }
+        let now = self.clock.try_now();
+        self.state_mut().started(particulates, now);
+        Ok(())
+    }
+
+    pub async fn stop_measurement(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I::Error>> {
+        self.write_command::<cmd::StopMeasurement>(delay).await?;
+        self.state_mut().mode = Mode::Idle;
+        Ok(())
+    }
+
+    /// Switches the sensor to measuring in the provided [`ParticulateMode`].
+    ///
+    /// The sensor can only change measurement modes while idle, so if it is
+    /// measuring in a different particulate mode, measurement is stopped and
+    /// then restarted in the new mode. If the sensor is already measuring in
+    /// the requested mode, this does nothing.
+    ///
+    /// # Notes
+    ///
+    /// - The sensor must be in measurement mode. Use the
+    ///   [`start_measurement()`](Self::start_measurement) method to enter
+    ///   measurement mode.
+    ///
+    /// - When particulate matter measurement is enabled, the readings take
+    ///   some time to stabilize after the fan starts.
+    pub async fn set_particulate_mode(
+        &mut self,
+        particulates: ParticulateMode,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I::Error>> {
+        self.state_mut().check_mode(Mode::Measuring)?;
+        if self.state().particulates == particulates {
+            return Ok(());
+        }
+        self.stop_measurement(delay).await?;
+        self.start_measurement(particulates, delay).await
+    }
+
+    /// Returns the [`ParticulateMode`] the sensor was last started in.
+    #[must_use]
+    pub fn particulate_mode(&self) -> ParticulateMode {
+        self.state().particulates
+    }
+
+    pub async fn read_warm_start_parameter(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<u16, Error<I::Error>> {
+        self.read_command::<cmd::WarmStartParameter>(delay).await
+    }
+
+    pub async fn set_warm_start_parameter(
+        &mut self,
+        delay: &mut impl DelayNs,
+        param: u16,
+    ) -> Result<(), Error<I::Error>> {
+        self.state_mut().check_mode(Mode::Idle)?;
+        self.write_data_command::<cmd::WarmStartParameter>(delay, param)
+            .await?;
+        self.state_mut().config.warm_start = Some(param);
+        Ok(())
+    }
+
+    /// Reads the sensor's temperature offset parameters.
+    pub async fn read_temperature_offset(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::TemperatureOffset, Error<I::Error>> {
+        self.read_command::<cmd::TemperatureOffset>(delay).await
+    }
+
+    /// Sets the sensor's temperature offset parameters.
+    ///
+    /// This may be called in any mode. The parameters are lost when the sensor
+    /// is reset or powered off, and are re-applied by
+    /// [`reapply_config()`](Self::reapply_config).
+    pub async fn set_temperature_offset(
+        &mut self,
+        delay: &mut impl DelayNs,
+        offset: msg::TemperatureOffset,
+    ) -> Result<(), Error<I::Error>> {
+        self.write_data_command::<cmd::TemperatureOffset>(delay, offset)
+            .await?;
+        self.state_mut().config.temperature_offset = Some(offset);
+        Ok(())
+    }
+
+    /// Reads the tuning parameters of the sensor's VOC index algorithm.
+    pub async fn read_voc_tuning(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::AlgorithmTuning, Error<I::Error>> {
+        self.read_command::<cmd::VocTuning>(delay).await
+    }
+
+    /// Sets the tuning parameters of the sensor's VOC index algorithm.
+    ///
+    /// The sensor must be idle. The parameters are lost when the sensor is
+    /// reset or powered off, and are re-applied by
+    /// [`reapply_config()`](Self::reapply_config).
+    pub async fn set_voc_tuning(
+        &mut self,
+        delay: &mut impl DelayNs,
+        tuning: msg::AlgorithmTuning,
+    ) -> Result<(), Error<I::Error>> {
+        self.state_mut().check_mode(Mode::Idle)?;
+        self.write_data_command::<cmd::VocTuning>(delay, tuning)
+            .await?;
+        self.state_mut().config.voc_tuning = Some(tuning);
+        Ok(())
+    }
+
+    /// Reads the tuning parameters of the sensor's NOx index algorithm.
+    pub async fn read_nox_tuning(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::AlgorithmTuning, Error<I::Error>> {
+        self.read_command::<cmd::NoxTuning>(delay).await
+    }
+
+    /// Sets the tuning parameters of the sensor's NOx index algorithm.
+    ///
+    /// The sensor must be idle. The parameters are lost when the sensor is
+    /// reset or powered off, and are re-applied by
+    /// [`reapply_config()`](Self::reapply_config).
+    pub async fn set_nox_tuning(
+        &mut self,
+        delay: &mut impl DelayNs,
+        tuning: msg::AlgorithmTuning,
+    ) -> Result<(), Error<I::Error>> {
+        self.state_mut().check_mode(Mode::Idle)?;
+        self.write_data_command::<cmd::NoxTuning>(delay, tuning)
+            .await?;
+        self.state_mut().config.nox_tuning = Some(tuning);
+        Ok(())
+    }
+
+    /// Reads the sensor's [`RhtAccelerationMode`](msg::RhtAccelerationMode).
+    pub async fn read_rht_acceleration_mode(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::RhtAccelerationMode, Error<I::Error>> {
+        self.read_command::<cmd::RhtAccelerationMode>(delay).await
+    }
+
+    /// Sets the sensor's [`RhtAccelerationMode`](msg::RhtAccelerationMode).
+    ///
+    /// The sensor must be idle. The mode is lost when the sensor is reset or
+    /// powered off, and is re-applied by
+    /// [`reapply_config()`](Self::reapply_config).
+    pub async fn set_rht_acceleration_mode(
+        &mut self,
+        delay: &mut impl DelayNs,
+        mode: msg::RhtAccelerationMode,
+    ) -> Result<(), Error<I::Error>> {
+        self.state_mut().check_mode(Mode::Idle)?;
+        self.write_data_command::<cmd::RhtAccelerationMode>(delay, mode)
+            .await?;
+        self.state_mut().config.rht_acceleration = Some(mode);
+        Ok(())
+    }
+
+    /// Reads the interval between automatic fan cleanings, in seconds.
+    pub async fn read_auto_cleaning_interval(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<u32, Error<I::Error>> {
+        self.read_command::<cmd::AutoCleaningInterval>(delay).await
+    }
+
+    /// Sets the interval between automatic fan cleanings, in seconds.
+    ///
+    /// This may be called in any mode. An interval of 0 disables automatic fan
+    /// cleaning. The interval is lost when the sensor is reset or powered off,
+    /// and is re-applied by [`reapply_config()`](Self::reapply_config).
+    pub async fn set_auto_cleaning_interval(
+        &mut self,
+        delay: &mut impl DelayNs,
+        interval_s: u32,
+    ) -> Result<(), Error<I::Error>> {
+        self.write_data_command::<cmd::AutoCleaningInterval>(delay, interval_s)
+            .await?;
+        self.state_mut().config.auto_cleaning_interval_s = Some(interval_s);
+        Ok(())
+    }
+
+    /// Writes each setting present in the provided [`Sen5xConfig`] to the
+    /// sensor.
+    ///
+    /// If the configuration contains any setting which can only be changed
+    /// while idle (see [`Sen5xConfig::requires_idle()`]), the sensor must be
+    /// idle. Applied settings are recorded in the driver's [`State`], and are
+    /// re-applied by [`reapply_config()`](Self::reapply_config).
+    pub async fn apply_config(
+        &mut self,
+        config: &Sen5xConfig,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I::Error>> {
+        if config.requires_idle() {
+            self.state_mut().check_mode(Mode::Idle)?;
+        }
+        if let Some(param) = config.warm_start {
+            self.set_warm_start_parameter(delay, param).await?;
+        }
+        if let Some(offset) = config.temperature_offset {
+            self.set_temperature_offset(delay, offset).await?;
+        }
+        if let Some(tuning) = config.voc_tuning {
+            self.set_voc_tuning(delay, tuning).await?;
+        }
+        if let Some(tuning) = config.nox_tuning {
+            self.set_nox_tuning(delay, tuning).await?;
+        }
+        if let Some(mode) = config.rht_acceleration {
+            self.set_rht_acceleration_mode(delay, mode).await?;
+        }
+        if let Some(interval_s) = config.auto_cleaning_interval_s {
+            self.set_auto_cleaning_interval(delay, interval_s).await?;
+        }
+        Ok(())
+    }
+
+    /// Reads the sensor's current configuration, with every setting present.
+    pub async fn read_config(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<Sen5xConfig, Error<I::Error>> {
+        Ok(Sen5xConfig {
+            warm_start: Some(self.read_warm_start_parameter(delay).await?),
+            temperature_offset: Some(self.read_temperature_offset(delay).await?),
+            voc_tuning: Some(self.read_voc_tuning(delay).await?),
+            nox_tuning: Some(self.read_nox_tuning(delay).await?),
+            rht_acceleration: Some(self.read_rht_acceleration_mode(delay).await?),
+            auto_cleaning_interval_s: Some(self.read_auto_cleaning_interval(delay).await?),
+        })
+    }
+
+    /// Writes the configuration previously applied through this driver back
+    /// to the sensor, such as after it was reset or lost power.
+    ///
+    /// See [`State::config()`] for the configuration which is re-applied. If
+    /// it contains any setting which can only be changed while idle, the
+    /// sensor must be idle.
+    pub async fn reapply_config(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I::Error>> {
+        let config = *self.state().config();
+        self.apply_config(&config, delay).await
+    }
+
+    pub async fn reset(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<I::Error>> {
+        self.write_command::<cmd::Reset>(delay).await?;
+        self.state_mut().mode = Mode::Idle;
+        Ok(())
+    }
+
+    pub async fn wait_for_data(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<I::Error>> {
+        self.wait_for_data_with_interval(delay, 20).await
+    }
+
+    /// Waits until a measurement is ready, polling the sensor's data-ready flag
+    /// every `interval_ms` milliseconds.
+    ///
+    /// This method will wait indefinitely if the sensor never reports that data
+    /// is ready. Use
+    /// [`wait_for_data_with_timeout()`](Self::wait_for_data_with_timeout) to
+    /// bound the wait.
+    pub async fn wait_for_data_with_interval(
+        &mut self,
+        delay: &mut impl DelayNs,
+        interval_ms: u32,
+    ) -> Result<(), Error<I::Error>> {
+        self.poll_data_ready(delay, interval_ms, None).await
+    }
+
+    /// Waits until a measurement is ready, polling the sensor's data-ready flag
+    /// every `interval_ms` milliseconds, and returning [`Error::Timeout`] if no
+    /// measurement is ready after `timeout_ms` milliseconds.
+    ///
+    /// # Notes
+    ///
+    /// - If the driver has a [`Clock`], the elapsed time is read from the
+    ///   clock. Otherwise, it is tracked by summing the delays performed while
+    ///   polling, including the execution time of each data-ready command.
+    ///   Time spent performing I²C transactions is not counted, so without a
+    ///   clock, the actual time before a timeout may be somewhat longer than
+    ///   `timeout_ms`.
+    ///
+    /// - The sensor produces a new measurement once per second, so timeouts of
+    ///   less than a second may elapse even if the sensor is working normally.
+    pub async fn wait_for_data_with_timeout(
+        &mut self,
+        delay: &mut impl DelayNs,
+        interval_ms: u32,
+        timeout_ms: u32,
+    ) -> Result<(), Error<I::Error>> {
+        self.poll_data_ready(delay, interval_ms, Some(timeout_ms))
+            .await
+    }
+
+    async fn poll_data_ready(
+        &mut self,
+        delay: &mut impl DelayNs,
+        interval_ms: u32,
+        timeout_ms: Option<u32>,
+    ) -> Result<(), Error<I::Error>> {
+        self.state_mut().check_mode(Mode::Measuring)?;
+        let started_at = self.clock.try_now();
+        // The delays performed so far, used if the driver has no clock.
+        let mut waited_ms: u64 = 0;
+        while !self.data_ready(delay).await? {
+            // Without a clock, the command's execution time has been waited in
+            // full.
+            waited_ms += cmd::ReadDataReady::EXECUTION_MS as u64;
+            let elapsed_ms = match (started_at, self.clock.try_now()) {
+                (Some(started_at), Some(now)) => now.millis_since(started_at),
+                _ => waited_ms,
+            };
+            if timeout_ms.is_some_and(|timeout| elapsed_ms >= u64::from(timeout)) {
+                self.state_mut().diagnostics.timeout();
+                return Err(Error::Timeout);
+            }
+            delay.delay_ms(interval_ms).await;
+            waited_ms += u64::from(interval_ms);
+        }
+        Ok(())
+    }
+
+    /// Waits until a measurement is ready and reads data from the sensor.
+    pub async fn measure(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::Measurements, Error<I::Error>> {
+        self.wait_for_data(delay).await?;
+        self.read_measurements_unchecked(delay).await
+    }
+
+    /// Waits until a measurement is ready and reads data from the sensor,
+    /// recovering from failures as directed by the provided [`Supervisor`].
+    ///
+    /// A measurement fails if no data is ready within the supervisor's data
+    /// timeout, if reading it fails, or if the sensor's status register
+    /// (which is read periodically) reports an error. Failures are retried,
+    /// or the sensor is restarted or reset and its configuration re-applied,
+    /// until a measurement succeeds. See [`Supervisor`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SensorFailed`] if every recovery action has been
+    /// exhausted, or the supervisor had already considered the sensor failed.
+    /// Returns [`Error::WrongMode`] if the sensor is not measuring.
+    pub async fn measure_supervised(
+        &mut self,
+        supervisor: &mut Supervisor,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::Measurements, Error<I::Error>> {
+        if supervisor.health() == Health::Failed {
+            return Err(Error::SensorFailed);
+        }
+        self.state_mut().check_mode(Mode::Measuring)?;
+        let particulates = self.state().particulates;
+        loop {
+            let mut error = match self.measure_checked(supervisor, delay).await {
+                Ok(measurements) => {
+                    supervisor.record_success();
+                    return Ok(measurements);
+                }
+                Err(error) => error,
+            };
+            loop {
+                let Some(action) = supervisor.record_failure(&error) else {
+                    return Err(Error::SensorFailed);
+                };
+                match self.recover(action, particulates, delay).await {
+                    Ok(()) => break,
+                    Err(e) => error = e,
+                }
+            }
+        }
+    }
+
+    async fn measure_checked(
+        &mut self,
+        supervisor: &mut Supervisor,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::Measurements, Error<I::Error>> {
+        self.poll_data_ready(delay, 20, Some(supervisor.data_timeout_ms()))
+            .await?;
+        let measurements = self.read_measurements_unchecked(delay).await?;
+        if supervisor.status_due() {
+            let status = self.read_device_status(delay).await?;
+            supervisor.status_read(status);
+            if status.intersects(msg::SensorStatus::ERROR) {
+                return Err(Error::DeviceStatus(status));
+            }
+        }
+        Ok(measurements)
+    }
+
+    async fn recover(
+        &mut self,
+        action: RecoveryAction,
+        particulates: ParticulateMode,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I::Error>> {
+        match action {
+            RecoveryAction::Retry => return Ok(()),
+            RecoveryAction::RestartMeasurement => {
+                self.write_command::<cmd::StopMeasurement>(delay).await?;
+                self.state_mut().mode = Mode::Idle;
+            }
+            RecoveryAction::Reset => {
+                self.reset(delay).await?;
+                self.reapply_config(delay).await?;
+            }
+        }
+        self.start_measurement(particulates, delay).await
+    }
+
+    /// Reads the measurement data from the sensor.
+    ///
+    /// # Notes
+    ///
+    /// - In order to read a measurement, the sensor must be in measurement
+    ///   mode. Use the [`start_measurement()`](Self::start_measurement) method
+    ///   to enter measurement mode.
+    ///
+    /// - This method does *not* wait for new data to be available. It may
+    ///   return the same data multiple times. Use the
+    ///   [`data_ready()`](Self::data_ready) method to check if new data is available.
+    pub async fn read_measurements(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::Measurements, Error<I::Error>> {
+        self.state_mut().check_mode(Mode::Measuring)?;
+        self.read_measurements_unchecked(delay).await
+    }
+
+    async fn read_measurements_unchecked(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::Measurements, Error<I::Error>> {
+        let measurements = self.read_command::<cmd::ReadMeasurement>(delay).await?;
+        let now = self.clock.try_now();
+        Ok(self.state_mut().measurements_read(measurements, now))
+    }
+
+    /// Reads raw temperature, relative humidity, VOC, and NOx signals from the
+    /// sensor.
+    ///
+    /// # Notes
+    ///
+    /// - In order to read a measurement, the sensor must be in measurement
+    ///   mode. Use the [`start_measurement()`](Self::start_measurement) method
+    ///   to enter measurement mode.
+    ///
+    /// - This method does *not* wait for new data to be available. It may
+    ///   return the same data multiple times. Use the
+    ///   [`data_ready()`](Self::data_ready) method to check if new data is
+    ///   available.
+    ///
+    /// - Sensirion does not provide a specification for interpreting these
+    ///   values. See the [application note on reading raw signals][appnote] for
+    ///   details.
+    ///
+    /// [appnote]: https://sensirion.com/media/documents/2B6FC1F3/649C3D0E/PS_AN_Read_RHT_VOC_and_NOx_RAW_signals_v2_D1.pdf
+    pub async fn read_raw_signals(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::RawSignals, Error<I::Error>> {
+        self.state_mut().check_mode(Mode::Measuring)?;
+        self.read_command::<cmd::ReadRawSignals>(delay).await
+    }
+
+    pub async fn start_fan_cleaning(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I::Error>> {
+        // The fan only runs while particulate matter is being measured.
+        self.state_mut().check_mode(Mode::Measuring)?;
+        if self.particulate_mode() != ParticulateMode::Enabled {
+            self.state_mut().diagnostics.mode_error();
+            return Err(Error::WrongMode(Mode::Measuring));
+        }
+        self.write_command::<cmd::StartFanCleaning>(delay).await?;
+        let now = self.clock.try_now();
+        self.state_mut().fan_cleaning_started(now);
+        Ok(())
+    }
+
+    /// Starts the fan cleaning procedure, and waits until it completes.
+    ///
+    /// The sensor's status register is polled every second until the
+    /// [`FAN_CLEANING`](msg::SensorStatus::FAN_CLEANING) bit clears.
+    /// Measurements produced while cleaning are read and discarded, so the
+    /// next measurement read after this method returns was taken after
+    /// cleaning completed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if cleaning has not completed after 20
+    /// seconds (the procedure normally takes about 10 seconds).
+    ///
+    /// Returns [`Error::WrongMode`] without starting cleaning if the sensor is
+    /// not in measurement mode with particulate matter enabled.
+    pub async fn clean_fan(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<I::Error>> {
+        self.start_fan_cleaning(delay).await?;
+        let mut elapsed_ms: u32 = 0;
+        loop {
+            delay.delay_ms(fan_cleaning::POLL_MS).await;
+            elapsed_ms = elapsed_ms.saturating_add(fan_cleaning::POLL_MS);
+            if self.data_ready(delay).await? {
+                self.read_measurements_unchecked(delay).await?;
+            }
+            let status = self.read_device_status(delay).await?;
+            if !status.contains(msg::SensorStatus::FAN_CLEANING) {
+                return Ok(());
+            }
+            if elapsed_ms >= fan_cleaning::TIMEOUT_MS {
+                self.state_mut().diagnostics.timeout();
+                return Err(Error::Timeout);
+            }
+        }
+    }
+
+    /// Waits for the sensor to become ready after it is powered on, and
+    /// verifies that it is a SEN5x, returning its [`SensorKind`].
+    ///
+    /// This waits 50 milliseconds for the sensor to start up, and then probes
+    /// it by reading its product name, retrying up to 5 times, 10 milliseconds
+    /// apart, while it does not acknowledge its address. The driver's
+    /// [`State`] is updated to reflect that the sensor is idle.
+    ///
+    /// Configuration written to the sensor before it lost power is not
+    /// restored. Use [`reapply_config()`](Self::reapply_config) to restore it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownDevice`] if the product name is not that of a
+    /// SEN50, SEN54, or SEN55, or the I²C error from the last attempt if the
+    /// sensor never responds.
+    pub async fn power_on(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<SensorKind, Error<I::Error>> {
+        delay.delay_ms(power::POWER_UP_MS).await;
+        {
+            let state = self.state_mut();
+            state.mode = Mode::Idle;
+            state.fan_cleaning = None;
+            state.executing = None;
+        }
+        let mut attempts = 1;
+        let name = loop {
+            match self.read_command_once::<cmd::ReadProductName>(delay).await {
+                Err(Error::I2cWrite(_) | Error::I2cRead(_)) if attempts < power::PROBE_ATTEMPTS => {
+                    delay.delay_ms(power::PROBE_INTERVAL_MS).await;
+                    attempts += 1;
+                }
+                res => break res?,
+            }
+        };
+        name.as_str().parse().map_err(|_| Error::UnknownDevice)
+    }
+
+    /// Power-cycles the sensor using a load switch, and waits for it to become
+    /// ready again.
+    ///
+    /// The load switch is controlled by the provided [`OutputPin`], which must
+    /// power the sensor when driven high. The pin is driven low for one
+    /// second, and then high, before waiting for the sensor as in
+    /// [`power_on()`](Self::power_on). The configuration previously applied
+    /// through the driver is then restored using
+    /// [`reapply_config()`](Self::reapply_config).
+    ///
+    /// This is the most drastic recovery available, for a sensor which no
+    /// longer responds to [`reset()`](Self::reset).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PowerPin`] if the pin could not be driven, or any error
+    /// returned by `power_on()` or `reapply_config()`.
+    pub async fn power_cycle(
+        &mut self,
+        power: &mut impl OutputPin,
+        delay: &mut impl DelayNs,
+    ) -> Result<SensorKind, Error<I::Error>> {
+        power.set_low().map_err(|_| Error::PowerPin)?;
+        self.state_mut().mode = Mode::Idle;
+        delay.delay_ms(power::POWER_OFF_MS).await;
+        power.set_high().map_err(|_| Error::PowerPin)?;
+        let kind = self.power_on(delay).await?;
+        self.reapply_config(delay).await?;
+        Ok(kind)
+    }
+
+    /// Reads the sensor's device status register.
+    ///
+    /// Error bits remain set until the sensor is reset. Reading the status
+    /// also updates whether subsequent measurements are flagged as taken
+    /// [during fan cleaning](msg::Measurements::during_fan_cleaning).
+    pub async fn read_device_status(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::SensorStatus, Error<I::Error>> {
+        let status = self.read_command::<cmd::ReadDeviceStatus>(delay).await?;
+        let now = self.clock.try_now();
+        self.state_mut().status_read(status, now);
+        Ok(status)
+    }
+
+    pub async fn read_product_name(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::RawString, Error<I::Error>> {
+        self.read_command::<cmd::ReadProductName>(delay).await
+    }
+
+    pub async fn read_serial_number(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<msg::RawString, Error<I::Error>> {
+        self.read_command::<cmd::ReadSerialNumber>(delay).await
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    idents(
+        Sen5x(sync, async = "Sen5xAsync"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, S, K> Sen5x<I, S, K>
+where
+    I: I2c,
+    S: BorrowMut<State>,
+    K: Clock,
+{
+    /// Waits until a measurement is ready and reads data from the sensor,
+    /// along with the time at which the sensor reported that it was ready.
+    ///
+    /// The timestamp is read from the driver's [`Clock`] as soon as the
+    /// sensor's data-ready flag is set, so its accuracy depends on the polling
+    /// interval used while waiting for data (20 milliseconds).
+    pub async fn measure_timestamped(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<TimestampedMeasurements, Error<I::Error>> {
+        self.wait_for_data(delay).await?;
+        let timestamp = self.clock.now();
+        let measurements = self.read_measurements_unchecked(delay).await?;
+        Ok(TimestampedMeasurements {
+            measurements,
+            timestamp,
+        })
+    }
+
+    /// Sends the data-ready command without waiting for it to execute.
+    ///
+    /// See [`PendingCommand`] for details on split-phase commands.
+    pub async fn begin_data_ready(&mut self) -> Result<PendingCommand<bool>, Error<I::Error>> {
+        self.begin_command::<cmd::ReadDataReady, _>().await
+    }
+
+    /// Sends the read measurement command without waiting for it to execute.
+    ///
+    /// The sensor must be in measurement mode. See [`PendingCommand`] for
+    /// details on split-phase commands.
+    pub async fn begin_read_measurements(
+        &mut self,
+    ) -> Result<PendingCommand<msg::Measurements>, Error<I::Error>> {
+        self.state_mut().check_mode(Mode::Measuring)?;
+        self.begin_command::<cmd::ReadMeasurement, _>().await
+    }
+
+    /// Sends the read raw signals command without waiting for it to execute.
+    ///
+    /// The sensor must be in measurement mode. See [`PendingCommand`] for
+    /// details on split-phase commands.
+    pub async fn begin_read_raw_signals(
+        &mut self,
+    ) -> Result<PendingCommand<msg::RawSignals>, Error<I::Error>> {
+        self.state_mut().check_mode(Mode::Measuring)?;
+        self.begin_command::<cmd::ReadRawSignals, _>().await
+    }
+
+    /// Sends the read device status command without waiting for it to
+    /// execute.
+    ///
+    /// See [`PendingCommand`] for details on split-phase commands.
+    pub async fn begin_read_device_status(
+        &mut self,
+    ) -> Result<PendingCommand<msg::SensorStatus>, Error<I::Error>> {
+        self.begin_command::<cmd::ReadDeviceStatus, _>().await
+    }
+
+    /// Starts measurement in the provided [`ParticulateMode`], without waiting
+    /// for the command to execute.
+    ///
+    /// The sensor will not accept another command until the returned
+    /// [`PendingCommand`] is ready.
+    pub async fn begin_start_measurement(
+        &mut self,
+        particulates: ParticulateMode,
+    ) -> Result<PendingCommand<()>, Error<I::Error>> {
+        let pending = match particulates {
+            ParticulateMode::Enabled => self.begin_command::<cmd::StartMeasurement, _>().await?,
+            ParticulateMode::Disabled => {
+                self.begin_command::<cmd::StartMeasurementNoParticulates, _>()
+                    .await?
+            }
+        };
+        let now = self.clock.now();
+        self.state_mut().started(particulates, Some(now));
+        Ok(pending)
+    }
+
+    /// Stops measurement, without waiting for the command to execute.
+    ///
+    /// The sensor will not accept another command until the returned
+    /// [`PendingCommand`] is ready, which takes 200 milliseconds.
+    pub async fn begin_stop_measurement(&mut self) -> Result<PendingCommand<()>, Error<I::Error>> {
+        let pending = self.begin_command::<cmd::StopMeasurement, _>().await?;
+        self.state_mut().mode = Mode::Idle;
+        Ok(pending)
+    }
+
+    /// Reads the response to a command started by one of the `begin_*()`
+    /// methods.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Busy`] without reading anything if the command is not
+    /// yet [ready](PendingCommand::is_ready) according to the driver's
+    /// [`Clock`]. The same `PendingCommand` may be finished again once it is
+    /// ready. See [`PendingCommand`] for details.
+    pub async fn finish_command<T: CommandOutput>(
+        &mut self,
+        pending: &PendingCommand<T>,
+    ) -> Result<T, Error<I::Error>> {
+        if !pending.is_ready(&self.clock) {
+            return Err(Error::Busy);
+        }
+        // A command sent after this one (such as by a method which waited for
+        // this one to execute) may still be executing.
+        let state = self.state_mut();
+        if state
+            .executing_until()
+            .is_some_and(|until| until <= pending.ready_at())
+        {
+            state.executing = None;
+        }
+        let mut buf = T::BUF;
+        let res = self.i2c.read(self.state().addr, buf.as_mut()).await;
+        self.state_mut().diagnostics.record_read(res)?;
+        let state = self.state_mut();
+        // The response is read no earlier than the command was ready.
+        let res = T::finish(&buf, state, pending.ready_at());
+        state.diagnostics.record_decode(res, T::crc_errors)
+    }
+
+    async fn begin_command<C: WriteCommand, T>(
+        &mut self,
+    ) -> Result<PendingCommand<T>, Error<I::Error>> {
+        if self
+            .state()
+            .executing_until()
+            .is_some_and(|until| until > self.clock.now())
+        {
+            return Err(Error::Busy);
+        }
+        let res = self.i2c.write(self.state().addr, &C::COMMAND).await;
+        self.state_mut().diagnostics.record_write(res)?;
+        let now = self.clock.now();
+        self.state_mut().executing = Some((now, C::EXECUTION_MS as u32));
+        Ok(PendingCommand::new(now, C::EXECUTION_MS))
+    }
+
+    /// Cleans the fan as in [`clean_fan()`](Self::clean_fan), if the provided
+    /// [`FanCleaningSchedule`] is due at the current time of the driver's
+    /// [`Clock`], returning `true` if the fan was cleaned.
+    ///
+    /// The completion of cleaning is recorded in the schedule.
+    pub async fn clean_fan_if_due(
+        &mut self,
+        schedule: &mut FanCleaningSchedule,
+        delay: &mut impl DelayNs,
+    ) -> Result<bool, Error<I::Error>> {
+        if !schedule.is_due(self.clock.now()) {
+            return Ok(false);
+        }
+        self.clean_fan(delay).await?;
+        schedule.record_cleaning(self.clock.now());
+        Ok(true)
+    }
+}
+
+// === impl Sen5xConfig ===
+
+#[maybe_async_cfg::maybe(
+    keep_self,
+    idents(
+        Sen5x(sync, async = "Sen5xAsync"),
+        apply(fn, sync),
+        read_from(fn, sync),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl Sen5xConfig {
+    /// Writes this configuration to the sensor.
+    ///
+    /// See the driver's `apply_config()` method for details.
+    pub async fn apply<I, S, K>(
+        &self,
+        sensor: &mut Sen5x<I, S, K>,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I::Error>>
+    where
+        I: I2c,
+        S: BorrowMut<State>,
+        K: OptionalClock,
+    {
+        sensor.apply_config(self, delay).await
+    }
+
+    /// Reads the sensor's current configuration.
+    pub async fn read_from<I, S, K>(
+        sensor: &mut Sen5x<I, S, K>,
+        delay: &mut impl DelayNs,
+    ) -> Result<Self, Error<I::Error>>
+    where
+        I: I2c,
+        S: BorrowMut<State>,
+        K: OptionalClock,
+    {
+        sensor.read_config(delay).await
+    }
+}
@@ -0,0 +1,250 @@
+use super::Sen5x;
+#[cfg(feature = "embedded-hal-async")]
+use super::Sen5xAsync;
+use crate::{
+    cmd::{self, WriteCommand},
+    msg,
+    mux::{MuxChannel, DEFAULT_MUX_ADDR, MUX_CHANNELS},
+    Error, Mode, ParticulateMode, RetryPolicy, State,
+};
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+#[cfg(feature = "embedded-hal-async")]
+use embedded_hal_async::{delay::DelayNs as AsyncDelayNs, i2c::I2c as AsyncI2c};
+
+/// A blocking driver for up to 8 SEN5x sensors behind a TCA9548A or PCA9548
+/// I²C multiplexer.
+///
+/// All SEN5x sensors share the fixed I²C address `0x69`, so multiple sensors
+/// on one bus must be placed behind a multiplexer. Sensor `n` in the array is
+/// connected to channel `n` of the multiplexer, which is selected before each
+/// transaction with that sensor. A separate [`State`] is tracked for each
+/// sensor.
+///
+/// Individual sensors may be accessed using the [`sensor()`](Self::sensor)
+/// method, which returns a [`Sen5x`] driver for that sensor's channel.
+pub struct Sen5xArray<I, const N: usize> {
+    i2c: I,
+    mux_addr: u8,
+    states: [State; N],
+}
+
+/// An asynchronous driver for up to 8 SEN5x sensors behind a TCA9548A or PCA9548
+/// I²C multiplexer.
+///
+/// All SEN5x sensors share the fixed I²C address `0x69`, so multiple sensors
+/// on one bus must be placed behind a multiplexer. Sensor `n` in the array is
+/// connected to channel `n` of the multiplexer, which is selected before each
+/// transaction with that sensor. A separate [`State`] is tracked for each
+/// sensor.
+///
+/// Individual sensors may be accessed using the [`sensor()`](Self::sensor)
+/// method, which returns a [`Sen5xAsync`] driver for that sensor's channel.
+#[cfg(feature = "embedded-hal-async")]
+pub struct Sen5xArrayAsync<I, const N: usize> {
+    i2c: I,
+    mux_addr: u8,
+    states: [State; N],
+}
+
+// === impl Sen5xArray ===
+
+#[maybe_async_cfg::maybe(
+    idents(
+        Sen5xArray(sync, async = "Sen5xArrayAsync"),
+        Sen5x(sync, async = "Sen5xAsync"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, const N: usize> Sen5xArray<I, N> {
+    /// Returns a new array of `N` sensors, behind a multiplexer at its default
+    /// I²C address (`0x70`).
+    ///
+    /// `N` must be at most 8, the number of channels on the multiplexer.
+    pub const fn new(i2c: I) -> Self {
+        const { assert!(N <= MUX_CHANNELS, "a TCA9548A has only 8 channels") };
+        Self {
+            i2c,
+            mux_addr: DEFAULT_MUX_ADDR,
+            states: [State::new(); N],
+        }
+    }
+
+    /// Set the I²C address of the multiplexer.
+    #[inline]
+    #[must_use]
+    pub const fn with_mux_address(mut self, addr: u8) -> Self {
+        self.mux_addr = addr;
+        self
+    }
+
+    /// Set the [`RetryPolicy`] used when reading from every sensor in the
+    /// array.
+    #[must_use]
+    pub const fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        let mut i = 0;
+        while i < N {
+            self.states[i] = self.states[i].with_retry_policy(retry);
+            i += 1;
+        }
+        self
+    }
+
+    /// Returns the [`State`] of the sensor on `channel`.
+    ///
+    /// # Panics
+    ///
+    /// If `channel` is not less than `N`.
+    #[must_use]
+    pub fn state(&self, channel: usize) -> &State {
+        &self.states[channel]
+    }
+
+    /// Returns the [`State`]s of all sensors in the array.
+    #[must_use]
+    pub fn states(&self) -> &[State; N] {
+        &self.states
+    }
+
+    /// Consumes the array, returning the I²C bus and the [`State`]s of all
+    /// sensors.
+    #[must_use]
+    pub fn into_parts(self) -> (I, [State; N]) {
+        (self.i2c, self.states)
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    idents(
+        Sen5xArray(sync, async = "Sen5xArrayAsync"),
+        Sen5x(sync, async = "Sen5xAsync"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, const N: usize> Sen5xArray<I, N>
+where
+    I: I2c,
+{
+    /// Returns a [`Sen5x`] driver for the sensor on `channel`.
+    ///
+    /// # Panics
+    ///
+    /// If `channel` is not less than `N`.
+    pub fn sensor(&mut self, channel: usize) -> Sen5x<MuxChannel<'_, I>, &mut State> {
+        let state = &mut self.states[channel];
+        Sen5x::from_parts(
+            MuxChannel::new(&mut self.i2c, self.mux_addr, channel as u8),
+            state,
+        )
+    }
+
+    /// Starts measurement on every sensor in the array, returning the result
+    /// for each sensor.
+    pub async fn start_measurement_all(
+        &mut self,
+        particulates: ParticulateMode,
+        delay: &mut impl DelayNs,
+    ) -> [Result<(), Error<I::Error>>; N] {
+        let mut results = core::array::from_fn(|_| Ok(()));
+        for (ch, result) in results.iter_mut().enumerate() {
+            *result = self.sensor(ch).start_measurement(particulates, delay).await;
+        }
+        results
+    }
+
+    /// Stops measurement on every sensor in the array, returning the result
+    /// for each sensor.
+    pub async fn stop_measurement_all(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> [Result<(), Error<I::Error>>; N] {
+        let mut results = core::array::from_fn(|_| Ok(()));
+        for (ch, result) in results.iter_mut().enumerate() {
+            *result = self.sensor(ch).stop_measurement(delay).await;
+        }
+        results
+    }
+
+    /// Waits until every sensor in the array has a measurement ready, and
+    /// reads them.
+    ///
+    /// Rather than waiting for each sensor in turn, the data-ready flags of all
+    /// sensors are polled together, and each sensor's measurement is read as
+    /// soon as it is ready. Since the sensors measure concurrently, this takes
+    /// about as long as waiting for a single sensor.
+    ///
+    /// Errors are returned per sensor, and do not prevent measurements being
+    /// read from the other sensors. This method will wait indefinitely if a
+    /// sensor never reports that data is ready. Use
+    /// [`measure_all_with_timeout()`](Self::measure_all_with_timeout) to bound
+    /// the wait.
+    pub async fn measure_all(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> [Result<msg::Measurements, Error<I::Error>>; N] {
+        self.poll_all(delay, 20, None).await
+    }
+
+    /// Waits until every sensor in the array has a measurement ready, polling
+    /// every `interval_ms` milliseconds, and reads them.
+    ///
+    /// Sensors which do not have a measurement ready after `timeout_ms`
+    /// milliseconds return [`Error::Timeout`]. See
+    /// the driver's `wait_for_data_with_timeout()` method for details on how the
+    /// elapsed time is tracked.
+    pub async fn measure_all_with_timeout(
+        &mut self,
+        delay: &mut impl DelayNs,
+        interval_ms: u32,
+        timeout_ms: u32,
+    ) -> [Result<msg::Measurements, Error<I::Error>>; N] {
+        self.poll_all(delay, interval_ms, Some(timeout_ms)).await
+    }
+
+    async fn poll_all(
+        &mut self,
+        delay: &mut impl DelayNs,
+        interval_ms: u32,
+        timeout_ms: Option<u32>,
+    ) -> [Result<msg::Measurements, Error<I::Error>>; N] {
+        let mut results: [Option<Result<msg::Measurements, Error<I::Error>>>; N] =
+            core::array::from_fn(|ch| self.states[ch].check_mode(Mode::Measuring).err().map(Err));
+        let mut elapsed_ms: u32 = 0;
+        loop {
+            for (ch, result) in results.iter_mut().enumerate() {
+                if result.is_some() {
+                    continue;
+                }
+                let mut sensor = self.sensor(ch);
+                match sensor.data_ready(delay).await {
+                    Ok(false) => {}
+                    Ok(true) => *result = Some(sensor.read_measurements(delay).await),
+                    Err(e) => *result = Some(Err(e)),
+                }
+                elapsed_ms = elapsed_ms.saturating_add(cmd::ReadDataReady::EXECUTION_MS as u32);
+            }
+
+            if results.iter().all(Option::is_some) {
+                break;
+            }
+
+            elapsed_ms = elapsed_ms.saturating_add(interval_ms);
+            if timeout_ms.is_some_and(|timeout| elapsed_ms >= timeout) {
+                for (state, result) in self.states.iter_mut().zip(&mut results) {
+                    if result.is_none() {
+                        state.diagnostics.timeout();
+                        *result = Some(Err(Error::Timeout));
+                    }
+                }
+                break;
+            }
+            delay.delay_ms(interval_ms).await;
+        }
+        results.map(|result| result.unwrap_or(Err(Error::Timeout)))
+    }
+}
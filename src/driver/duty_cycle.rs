@@ -0,0 +1,229 @@
+use super::Sen5x;
+#[cfg(feature = "embedded-hal-async")]
+use super::Sen5xAsync;
+use crate::{
+    duty_cycle::{DutyCycle, Phase},
+    msg, Error, Mode, OptionalClock, ParticulateMode, State,
+};
+use core::borrow::BorrowMut;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+#[cfg(feature = "embedded-hal-async")]
+use embedded_hal_async::{delay::DelayNs as AsyncDelayNs, i2c::I2c as AsyncI2c};
+
+/// An iterator over duty-cycled measurements from a [`Sen5x`].
+///
+/// This is returned by [`Sen5x::duty_cycled()`]. Each call to
+/// [`next()`](Iterator::next) returns the next measurement in the current
+/// burst, starting the next burst (and blocking until it is ready) if the
+/// current burst is complete. See [`DutyCycle`] for details. The iterator
+/// never ends.
+///
+/// # Errors
+///
+/// Errors are yielded without ending the iterator. If an error occurs while
+/// starting or stopping a burst, that step is retried the next time the
+/// iterator is advanced.
+pub struct DutyCycleIter<'a, I, D, S = State, K = ()> {
+    sensor: &'a mut Sen5x<I, S, K>,
+    delay: &'a mut D,
+    cycle: DutyCycle,
+    phase: Phase,
+}
+
+/// A stream of duty-cycled measurements from a [`Sen5xAsync`].
+///
+/// This is returned by [`Sen5xAsync::duty_cycled()`]. Each call to
+/// [`next()`](Self::next) returns the next measurement in the current burst,
+/// starting the next burst (and waiting until it is ready) if the current
+/// burst is complete. See [`DutyCycle`] for details.
+///
+/// # Errors
+///
+/// Errors are returned from [`next()`](Self::next) without ending the stream.
+/// If an error occurs while starting or stopping a burst, that step is retried
+/// the next time the stream is polled.
+#[cfg(feature = "embedded-hal-async")]
+pub struct DutyCycleStream<'a, I, D, S = State, K = ()> {
+    sensor: &'a mut Sen5xAsync<I, S, K>,
+    delay: &'a mut D,
+    cycle: DutyCycle,
+    phase: Phase,
+}
+
+// === impl Sen5x ===
+
+#[maybe_async_cfg::maybe(
+    idents(
+        Sen5x(sync, async = "Sen5xAsync"),
+        DutyCycleIter(sync, async = "DutyCycleStream"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, S, K> Sen5x<I, S, K>
+where
+    I: I2c,
+    S: BorrowMut<State>,
+    K: OptionalClock,
+{
+    /// Returns an iterator (or, for the asynchronous driver, a stream) which
+    /// takes bursts of measurements as configured by the provided
+    /// [`DutyCycle`].
+    ///
+    /// The first burst is started when the first measurement is requested. If
+    /// the sensor is already measuring with particulate matter enabled, the
+    /// first burst begins immediately, without waiting for the warmup time.
+    pub fn duty_cycled<'a, D: DelayNs>(
+        &'a mut self,
+        cycle: DutyCycle,
+        delay: &'a mut D,
+    ) -> DutyCycleIter<'a, I, D, S, K> {
+        DutyCycleIter {
+            sensor: self,
+            delay,
+            cycle,
+            phase: Phase::Start { off_ms: None },
+        }
+    }
+}
+
+// === impl DutyCycleIter ===
+
+#[maybe_async_cfg::maybe(
+    idents(DutyCycleIter(sync, async = "DutyCycleStream")),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, D, S, K> DutyCycleIter<'_, I, D, S, K> {
+    /// Returns the [`DutyCycle`] configuration used by this iterator or
+    /// stream.
+    #[must_use]
+    pub fn cycle(&self) -> &DutyCycle {
+        &self.cycle
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    idents(
+        DutyCycleIter(sync, async = "DutyCycleStream"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, D, S, K> DutyCycleIter<'_, I, D, S, K>
+where
+    I: I2c,
+    D: DelayNs,
+    S: BorrowMut<State>,
+    K: OptionalClock,
+{
+    /// Returns the next measurement, starting a new burst of measurements if
+    /// necessary.
+    async fn next_measurement(&mut self) -> Result<msg::Measurements, Error<I::Error>> {
+        loop {
+            match self.phase {
+                Phase::Start { off_ms } => {
+                    self.start_burst(off_ms).await?;
+                    self.phase = Phase::Sampling {
+                        remaining: self.cycle.samples(),
+                    };
+                }
+                Phase::Sampling { remaining } => {
+                    let res = self.sensor.measure(self.delay).await;
+                    if res.is_ok() {
+                        self.phase = match remaining {
+                            0 | 1 => Phase::Stop,
+                            remaining => Phase::Sampling {
+                                remaining: remaining - 1,
+                            },
+                        };
+                    }
+                    return res;
+                }
+                Phase::Stop => {
+                    let off_ms = self.stop_burst().await?;
+                    self.phase = Phase::Start { off_ms };
+                }
+            }
+        }
+    }
+
+    async fn start_burst(&mut self, off_ms: Option<u32>) -> Result<(), Error<I::Error>> {
+        match self.sensor.state().mode() {
+            Mode::Measuring if self.sensor.particulate_mode() == ParticulateMode::Enabled => {
+                return Ok(());
+            }
+            Mode::Measuring => {
+                self.sensor
+                    .set_particulate_mode(ParticulateMode::Enabled, self.delay)
+                    .await?;
+            }
+            Mode::Idle => {
+                if let Some(off_ms) = off_ms {
+                    let param = self.cycle.warm_start_parameter(off_ms);
+                    self.sensor
+                        .set_warm_start_parameter(self.delay, param)
+                        .await?;
+                }
+                self.sensor
+                    .start_measurement(ParticulateMode::Enabled, self.delay)
+                    .await?;
+            }
+        }
+        self.delay.delay_ms(self.cycle.warmup_ms()).await;
+        Ok(())
+    }
+
+    async fn stop_burst(&mut self) -> Result<Option<u32>, Error<I::Error>> {
+        let sleep_ms = self.cycle.sleep_ms();
+        let off_ms = match self.cycle.between_bursts() {
+            Some(particulates) => {
+                self.sensor
+                    .set_particulate_mode(particulates, self.delay)
+                    .await?;
+                None
+            }
+            None => {
+                self.sensor.stop_measurement(self.delay).await?;
+                Some(sleep_ms)
+            }
+        };
+        self.delay.delay_ms(sleep_ms).await;
+        Ok(off_ms)
+    }
+}
+
+impl<I, D, S, K> Iterator for DutyCycleIter<'_, I, D, S, K>
+where
+    I: I2c,
+    D: DelayNs,
+    S: BorrowMut<State>,
+    K: OptionalClock,
+{
+    type Item = Result<msg::Measurements, Error<I::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_measurement())
+    }
+}
+
+// === impl DutyCycleStream ===
+
+#[cfg(feature = "embedded-hal-async")]
+impl<I, D, S, K> DutyCycleStream<'_, I, D, S, K>
+where
+    I: AsyncI2c,
+    D: AsyncDelayNs,
+    S: BorrowMut<State>,
+    K: OptionalClock,
+{
+    /// Returns the next measurement, starting a new burst of measurements if
+    /// necessary.
+    pub async fn next(&mut self) -> Result<msg::Measurements, Error<I::Error>> {
+        self.next_measurement().await
+    }
+}
@@ -0,0 +1,172 @@
+use super::Sen5x;
+#[cfg(feature = "embedded-hal-async")]
+use super::Sen5xAsync;
+use crate::{Error, OptionalClock, Sample, State};
+use core::borrow::BorrowMut;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+#[cfg(feature = "embedded-hal-async")]
+use embedded_hal_async::{delay::DelayNs as AsyncDelayNs, i2c::I2c as AsyncI2c};
+
+/// An iterator over measurements from a [`Sen5x`].
+///
+/// This is returned by [`Sen5x::measurements()`]. Each call to
+/// [`next()`](Iterator::next) blocks until the sensor has a new measurement
+/// ready, and then reads it. The iterator never ends.
+///
+/// # Back-pressure
+///
+/// Measurements are only read from the sensor when the iterator is advanced.
+/// The sensor produces a new measurement every second; if the iterator is
+/// advanced less often than that, intermediate measurements are not buffered
+/// and are skipped.
+///
+/// # Errors
+///
+/// Errors are yielded without ending the iterator, so transient errors (such
+/// as a CRC mismatch) may be logged and ignored. Errors which will never
+/// resolve, such as [`Error::WrongMode`] if the sensor is not in measurement
+/// mode, will be yielded every time the iterator is advanced.
+pub struct MeasurementIter<'a, I, D, S = State, K = ()> {
+    sensor: &'a mut Sen5x<I, S, K>,
+    delay: &'a mut D,
+    raw_signals: bool,
+}
+
+/// A continuous stream of measurements from a [`Sen5xAsync`].
+///
+/// This is returned by [`Sen5xAsync::measurements()`]. Each call to
+/// [`next()`](Self::next) waits until the sensor has a new measurement ready,
+/// and then reads it.
+///
+/// # Back-pressure
+///
+/// Measurements are only read from the sensor when [`next()`](Self::next) is
+/// called. The sensor produces a new measurement every second; if the stream
+/// is polled less often than that, intermediate measurements are not buffered
+/// and are skipped.
+///
+/// # Errors
+///
+/// Errors are returned from [`next()`](Self::next) without ending the stream,
+/// so transient errors (such as a CRC mismatch) may be logged and ignored.
+/// Errors which will never resolve, such as [`Error::WrongMode`] if the sensor
+/// is not in measurement mode, will be returned every time the stream is
+/// polled.
+#[cfg(feature = "embedded-hal-async")]
+pub struct MeasurementStream<'a, I, D, S = State, K = ()> {
+    sensor: &'a mut Sen5xAsync<I, S, K>,
+    delay: &'a mut D,
+    raw_signals: bool,
+}
+
+// === impl Sen5x ===
+
+#[maybe_async_cfg::maybe(
+    idents(
+        Sen5x(sync, async = "Sen5xAsync"),
+        MeasurementIter(sync, async = "MeasurementStream"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, S, K> Sen5x<I, S, K>
+where
+    I: I2c,
+    S: BorrowMut<State>,
+    K: OptionalClock,
+{
+    /// Returns an iterator (or, for the asynchronous driver, a stream) which
+    /// yields each new measurement from the sensor as it becomes ready.
+    ///
+    /// The sensor must be in measurement mode. Use the
+    /// [`start_measurement()`](Self::start_measurement) method to enter
+    /// measurement mode.
+    pub fn measurements<'a, D: DelayNs>(
+        &'a mut self,
+        delay: &'a mut D,
+    ) -> MeasurementIter<'a, I, D, S, K> {
+        MeasurementIter {
+            sensor: self,
+            delay,
+            raw_signals: false,
+        }
+    }
+}
+
+// === impl MeasurementIter ===
+
+#[maybe_async_cfg::maybe(
+    idents(
+        MeasurementIter(sync, async = "MeasurementStream"),
+        I2c(sync, async = "AsyncI2c"),
+        DelayNs(sync, async = "AsyncDelayNs"),
+    ),
+    sync(),
+    async(feature = "embedded-hal-async")
+)]
+impl<I, D, S, K> MeasurementIter<'_, I, D, S, K>
+where
+    I: I2c,
+    D: DelayNs,
+    S: BorrowMut<State>,
+    K: OptionalClock,
+{
+    /// Configures the measurements to also include the sensor's raw signals
+    /// from each measurement cycle.
+    ///
+    /// If this is enabled, the raw signals are read immediately after the
+    /// measurements, and returned in [`Sample::raw_signals`].
+    #[must_use]
+    pub fn with_raw_signals(self) -> Self {
+        Self {
+            raw_signals: true,
+            ..self
+        }
+    }
+
+    async fn read_sample(&mut self) -> Result<Sample, Error<I::Error>> {
+        let measurements = self.sensor.measure(self.delay).await?;
+        let raw_signals = if self.raw_signals {
+            Some(self.sensor.read_raw_signals(self.delay).await?)
+        } else {
+            None
+        };
+        Ok(Sample {
+            measurements,
+            raw_signals,
+        })
+    }
+}
+
+impl<I, D, S, K> Iterator for MeasurementIter<'_, I, D, S, K>
+where
+    I: I2c,
+    D: DelayNs,
+    S: BorrowMut<State>,
+    K: OptionalClock,
+{
+    type Item = Result<Sample, Error<I::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.read_sample())
+    }
+}
+
+// === impl MeasurementStream ===
+
+#[cfg(feature = "embedded-hal-async")]
+impl<I, D, S, K> MeasurementStream<'_, I, D, S, K>
+where
+    I: AsyncI2c,
+    D: AsyncDelayNs,
+    S: BorrowMut<State>,
+    K: OptionalClock,
+{
+    /// Waits for the next measurement to become ready, and reads it from the
+    /// sensor.
+    pub async fn next(&mut self) -> Result<Sample, Error<I::Error>> {
+        self.read_sample().await
+    }
+}
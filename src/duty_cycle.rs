@@ -0,0 +1,239 @@
+use crate::ParticulateMode;
+
+/// Configuration for duty-cycled, low-power measurement.
+///
+/// Rather than measuring continuously, a duty-cycled sensor repeatedly:
+///
+/// 1. Starts measurement, and waits for the particulate matter readings to
+///    stabilize,
+/// 2. Takes a burst of [`samples`](Self::with_samples) measurements, one per
+///    second,
+/// 3. Stops measurement (or switches to RHT/gas-only measurement, if
+///    [`with_gas_only_between_bursts`](Self::with_gas_only_between_bursts) is
+///    set), and waits for the remainder of the [`period`](Self::new).
+///
+/// This is used by [`Sen5x::duty_cycled()`](crate::Sen5x::duty_cycled) and
+/// [`Sen5xAsync::duty_cycled()`](crate::Sen5xAsync::duty_cycled).
+///
+/// # Warm starts
+///
+/// When the sensor is stopped between bursts, it cools down, and its
+/// temperature compensation must re-learn the sensor's self-heating after
+/// each restart. To keep the compensation sensible across cycles, the sensor's
+/// warm start parameter is set before each burst (other than the first),
+/// based on how long the sensor was stopped: `65535` (a fully warm start) if
+/// it was restarted immediately, decreasing linearly to `0` (a cold start) if
+/// it was stopped for the [warm start
+/// decay](Self::with_warm_start_decay_ms) time or longer.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct DutyCycle {
+    period_ms: u32,
+    warmup_ms: u32,
+    samples: u8,
+    gas_only_between_bursts: bool,
+    warm_start_decay_ms: u32,
+}
+
+// === impl DutyCycle ===
+
+impl DutyCycle {
+    /// The default time waited after starting measurement for the particulate
+    /// matter readings to stabilize.
+    pub const DEFAULT_WARMUP_MS: u32 = 30_000;
+
+    /// The default time after which a stopped sensor is considered to have
+    /// cooled down completely.
+    pub const DEFAULT_WARM_START_DECAY_MS: u32 = 10 * 60 * 1000;
+
+    /// Returns a new `DutyCycle` which starts a burst of measurements every
+    /// `period_ms` milliseconds.
+    ///
+    /// By default, a burst consists of a single measurement, taken after
+    /// [`DEFAULT_WARMUP_MS`](Self::DEFAULT_WARMUP_MS), and the sensor is
+    /// stopped between bursts.
+    #[must_use]
+    pub const fn new(period_ms: u32) -> Self {
+        Self {
+            period_ms,
+            warmup_ms: Self::DEFAULT_WARMUP_MS,
+            samples: 1,
+            gas_only_between_bursts: false,
+            warm_start_decay_ms: Self::DEFAULT_WARM_START_DECAY_MS,
+        }
+    }
+
+    /// Set the time to wait after starting measurement, before taking the
+    /// first sample of a burst.
+    ///
+    /// Sensirion specifies that particulate matter readings stabilize within
+    /// about 30 seconds of starting measurement.
+    #[inline]
+    #[must_use]
+    pub const fn with_warmup_ms(mut self, warmup_ms: u32) -> Self {
+        self.warmup_ms = warmup_ms;
+        self
+    }
+
+    /// Set the number of measurements taken in each burst.
+    ///
+    /// A value of 0 is treated as 1.
+    #[inline]
+    #[must_use]
+    pub const fn with_samples(mut self, samples: u8) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// If `true`, the sensor is kept in RHT/gas-only measurement mode
+    /// ([`ParticulateMode::Disabled`]) between bursts, rather than being
+    /// stopped.
+    ///
+    /// This uses more power than stopping the sensor, but allows the VOC and
+    /// NOx gas index algorithms to keep running, and keeps the sensor's
+    /// temperature compensation warm. Only the fan and laser used for
+    /// particulate matter measurement are duty-cycled.
+    #[inline]
+    #[must_use]
+    pub const fn with_gas_only_between_bursts(mut self, gas_only: bool) -> Self {
+        self.gas_only_between_bursts = gas_only;
+        self
+    }
+
+    /// Set the time after which a stopped sensor is considered to have cooled
+    /// down completely.
+    ///
+    /// See [the type-level documentation](Self#warm-starts) for details.
+    #[inline]
+    #[must_use]
+    pub const fn with_warm_start_decay_ms(mut self, decay_ms: u32) -> Self {
+        self.warm_start_decay_ms = decay_ms;
+        self
+    }
+
+    /// Returns the period between the start of each burst, in milliseconds.
+    #[inline]
+    #[must_use]
+    pub const fn period_ms(&self) -> u32 {
+        self.period_ms
+    }
+
+    /// Returns the number of measurements taken in each burst.
+    #[inline]
+    #[must_use]
+    pub const fn samples(&self) -> u8 {
+        if self.samples == 0 {
+            1
+        } else {
+            self.samples
+        }
+    }
+
+    /// Returns the [`ParticulateMode`] the sensor is left in between bursts,
+    /// or [`None`] if the sensor is stopped.
+    #[must_use]
+    pub const fn between_bursts(&self) -> Option<ParticulateMode> {
+        if self.gas_only_between_bursts {
+            Some(ParticulateMode::Disabled)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) const fn warmup_ms(&self) -> u32 {
+        self.warmup_ms
+    }
+
+    /// Returns the time to wait between the end of one burst and the start of
+    /// the next.
+    ///
+    /// The duration of a burst is estimated as the warmup time plus one second
+    /// per sample, so the actual period may be somewhat longer than
+    /// configured.
+    pub(crate) const fn sleep_ms(&self) -> u32 {
+        let burst_ms = self
+            .warmup_ms
+            .saturating_add((self.samples() as u32).saturating_mul(1000));
+        self.period_ms.saturating_sub(burst_ms)
+    }
+
+    /// Returns the warm start parameter for a sensor which was stopped for
+    /// `off_ms` milliseconds.
+    pub(crate) const fn warm_start_parameter(&self, off_ms: u32) -> u16 {
+        if off_ms >= self.warm_start_decay_ms {
+            return 0;
+        }
+        let remaining = (self.warm_start_decay_ms - off_ms) as u64;
+        (u16::MAX as u64 * remaining / self.warm_start_decay_ms as u64) as u16
+    }
+}
+
+/// The progress of a duty-cycled measurement iterator or stream through the
+/// current cycle.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub(crate) enum Phase {
+    /// The next burst must be started.
+    ///
+    /// `off_ms` is the time the sensor has been stopped for, or [`None`] if
+    /// this is the first burst or the sensor was not stopped.
+    Start { off_ms: Option<u32> },
+    /// `remaining` samples are left to be taken in the current burst.
+    Sampling { remaining: u8 },
+    /// The burst is complete, and the sensor must be stopped before sleeping
+    /// until the next burst.
+    Stop,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warm_start_parameter_decays_linearly() {
+        let cycle = DutyCycle::new(60_000).with_warm_start_decay_ms(1000);
+        assert_eq!(cycle.warm_start_parameter(0), u16::MAX);
+        assert_eq!(cycle.warm_start_parameter(500), u16::MAX / 2);
+        assert_eq!(cycle.warm_start_parameter(999), 65);
+        assert_eq!(cycle.warm_start_parameter(1000), 0);
+        assert_eq!(cycle.warm_start_parameter(u32::MAX), 0);
+    }
+
+    #[test]
+    fn warm_start_parameter_without_decay() {
+        // With no decay time, every restart is a cold start, rather than a
+        // division by zero.
+        let cycle = DutyCycle::new(60_000).with_warm_start_decay_ms(0);
+        assert_eq!(cycle.warm_start_parameter(0), 0);
+        assert_eq!(cycle.warm_start_parameter(1000), 0);
+    }
+
+    #[test]
+    fn sleep_ms() {
+        let cycle = DutyCycle::new(60_000)
+            .with_warmup_ms(10_000)
+            .with_samples(5);
+        assert_eq!(cycle.sleep_ms(), 45_000);
+
+        // If the period is shorter than a burst, the next burst starts
+        // immediately.
+        let cycle = DutyCycle::new(10_000)
+            .with_warmup_ms(10_000)
+            .with_samples(5);
+        assert_eq!(cycle.sleep_ms(), 0);
+        let cycle = DutyCycle::new(1000).with_warmup_ms(u32::MAX);
+        assert_eq!(cycle.sleep_ms(), 0);
+    }
+
+    #[test]
+    fn zero_samples_is_one() {
+        assert_eq!(DutyCycle::new(60_000).with_samples(0).samples(), 1);
+        assert_eq!(DutyCycle::new(60_000).with_samples(3).samples(), 3);
+
+        // The sleep time accounts for the one sample actually taken.
+        let cycle = DutyCycle::new(60_000)
+            .with_warmup_ms(10_000)
+            .with_samples(0);
+        assert_eq!(cycle.sleep_ms(), 49_000);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::Instant;
+
+/// How often the sensor's status register is polled while waiting for fan
+/// cleaning to complete.
+pub(crate) const POLL_MS: u32 = 1000;
+
+/// The time after fan cleaning starts at which it is assumed to have completed,
+/// if the sensor's status register is not read: the procedure takes about 10
+/// seconds, plus one measurement interval.
+pub(crate) const DURATION_MS: u32 = 11_000;
+
+/// The time to wait for fan cleaning to complete before giving up. The
+/// cleaning procedure takes about 10 seconds.
+pub(crate) const TIMEOUT_MS: u32 = 20_000;
+
+/// A schedule for cleaning the sensor's fan at a fixed interval, only during
+/// permitted hours of the day.
+///
+/// The sensor cleans its fan automatically every 168 hours by default, whenever
+/// that interval happens to elapse. Since readings taken during cleaning are
+/// not meaningful, applications may prefer to disable automatic cleaning (by
+/// setting the automatic cleaning interval to 0) and instead use a schedule
+/// with the drivers' `clean_fan_if_due()` methods, so that cleaning happens at
+/// a time when nobody relies on the readings, such as overnight.
+///
+/// The permitted hours are determined from the [`Instant`]s passed to the
+/// schedule, by taking their time of day relative to the [`Clock`]'s epoch. A
+/// clock whose epoch is midnight in the local time zone, such as a real-time
+/// clock, must be used for the window to correspond to wall-clock time.
+///
+/// [`Clock`]: crate::Clock
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct FanCleaningSchedule {
+    interval_ms: u64,
+    window: Option<(u8, u8)>,
+    last: Option<Instant>,
+}
+
+const HOUR_MS: u64 = 60 * 60 * 1000;
+
+// === impl FanCleaningSchedule ===
+
+impl FanCleaningSchedule {
+    /// The sensor's default automatic cleaning interval, 168 hours (one
+    /// week), in milliseconds.
+    pub const DEFAULT_INTERVAL_MS: u64 = 168 * HOUR_MS;
+
+    /// Returns a new `FanCleaningSchedule` which cleans the fan every
+    /// `interval_ms` milliseconds, at any time of day.
+    ///
+    /// The fan is due to be cleaned immediately, unless the time of the last
+    /// cleaning is provided using
+    /// [`with_last_cleaning()`](Self::with_last_cleaning).
+    #[must_use]
+    pub const fn new(interval_ms: u64) -> Self {
+        Self {
+            interval_ms,
+            window: None,
+            last: None,
+        }
+    }
+
+    /// Only permit cleaning from `start_hour` (inclusive) until `end_hour`
+    /// (exclusive), in hours from 0 to 23.
+    ///
+    /// The window may wrap around midnight, such as from 22 until 6. If
+    /// `start_hour` equals `end_hour`, cleaning is permitted at any time.
+    #[inline]
+    #[must_use]
+    pub const fn with_window_hours(mut self, start_hour: u8, end_hour: u8) -> Self {
+        self.window = Some((start_hour % 24, end_hour % 24));
+        self
+    }
+
+    /// Set the time at which the fan was last cleaned, such as one restored
+    /// from persistent storage after a reboot.
+    #[inline]
+    #[must_use]
+    pub const fn with_last_cleaning(mut self, last: Instant) -> Self {
+        self.last = Some(last);
+        self
+    }
+
+    /// Returns the time at which the fan was last cleaned, if known.
+    #[inline]
+    #[must_use]
+    pub const fn last_cleaning(&self) -> Option<Instant> {
+        self.last
+    }
+
+    /// Returns `true` if cleaning is permitted at the time of day of `now`.
+    #[must_use]
+    pub const fn in_window(&self, now: Instant) -> bool {
+        let Some((start, end)) = self.window else {
+            return true;
+        };
+        let hour = ((now.as_millis() / HOUR_MS) % 24) as u8;
+        if start <= end {
+            start == end || (hour >= start && hour < end)
+        } else {
+            hour >= start || hour < end
+        }
+    }
+
+    /// Returns `true` if the fan should be cleaned at `now`: the interval has
+    /// elapsed since the last cleaning, and `now` is within the permitted
+    /// hours.
+    #[must_use]
+    pub const fn is_due(&self, now: Instant) -> bool {
+        let elapsed = match self.last {
+            Some(last) => now.millis_since(last) >= self.interval_ms,
+            None => true,
+        };
+        elapsed && self.in_window(now)
+    }
+
+    /// Records that the fan was cleaned at `now`.
+    pub fn record_cleaning(&mut self, now: Instant) {
+        self.last = Some(now);
+    }
+}
+
+impl Default for FanCleaningSchedule {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_INTERVAL_MS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u64, minute: u64) -> Instant {
+        Instant::from_millis(hour * HOUR_MS + minute * 60 * 1000)
+    }
+
+    #[test]
+    fn never_cleaned_is_due() {
+        let schedule = FanCleaningSchedule::default();
+        assert!(schedule.last_cleaning().is_none());
+        assert!(schedule.is_due(Instant::from_millis(0)));
+
+        // Even when never cleaned, cleaning waits for the window.
+        let schedule = FanCleaningSchedule::default().with_window_hours(2, 4);
+        assert!(!schedule.is_due(at(1, 59)));
+        assert!(schedule.is_due(at(2, 0)));
+    }
+
+    #[test]
+    fn interval_boundary() {
+        let mut schedule = FanCleaningSchedule::new(10 * HOUR_MS);
+        schedule.record_cleaning(at(1, 0));
+        assert!(schedule.last_cleaning() == Some(at(1, 0)));
+        assert!(!schedule.is_due(at(1, 0)));
+        assert!(!schedule.is_due(Instant::from_millis(11 * HOUR_MS - 1)));
+        assert!(schedule.is_due(at(11, 0)));
+
+        // A clock which went backwards is not due.
+        assert!(!schedule.is_due(at(0, 0)));
+    }
+
+    #[test]
+    fn window() {
+        let schedule = FanCleaningSchedule::new(0).with_window_hours(9, 17);
+        assert!(!schedule.in_window(at(8, 59)));
+        assert!(schedule.in_window(at(9, 0)));
+        assert!(schedule.in_window(at(16, 59)));
+        assert!(!schedule.in_window(at(17, 0)));
+
+        // The window applies on every day since the clock's epoch.
+        assert!(schedule.in_window(at(24 * 3 + 12, 0)));
+        assert!(!schedule.in_window(at(24 * 3 + 20, 0)));
+    }
+
+    #[test]
+    fn window_wraps_past_midnight() {
+        let schedule = FanCleaningSchedule::new(0).with_window_hours(22, 6);
+        assert!(!schedule.in_window(at(21, 59)));
+        assert!(schedule.in_window(at(22, 0)));
+        assert!(schedule.in_window(at(23, 59)));
+        assert!(schedule.in_window(at(24, 0)));
+        assert!(schedule.in_window(at(5, 59)));
+        assert!(!schedule.in_window(at(6, 0)));
+        assert!(!schedule.in_window(at(12, 0)));
+    }
+
+    #[test]
+    fn window_hours_are_normalised() {
+        // Hours of 24 or more wrap around, so 26 until 28 is 2 until 4.
+        let schedule = FanCleaningSchedule::new(0).with_window_hours(26, 28);
+        assert!(schedule == FanCleaningSchedule::new(0).with_window_hours(2, 4));
+        assert!(!schedule.in_window(at(1, 0)));
+        assert!(schedule.in_window(at(3, 0)));
+
+        // 24 until 0 is an empty-width window, permitting any time.
+        let schedule = FanCleaningSchedule::new(0).with_window_hours(24, 0);
+        assert!(schedule.in_window(at(0, 0)));
+        assert!(schedule.in_window(at(12, 0)));
+    }
+}
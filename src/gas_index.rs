@@ -0,0 +1,836 @@
+//! A port of the [Sensirion Gas Index Algorithm][algo] (version 3.2.0).
+//!
+//! The SEN54 and SEN55 run this algorithm on-chip to produce the VOC and NOx
+//! indices reported in [`Measurements`](crate::Measurements). Running it on
+//! the host, using the raw signals from
+//! [`RawSignals`](crate::RawSignals), allows using custom tuning parameters,
+//! comparing against the on-chip index, and persisting the algorithm's
+//! learned state independently of the sensor.
+//!
+//! [algo]: https://github.com/Sensirion/gas-index-algorithm
+
+#[doc(no_inline)]
+pub use crate::AlgorithmTuning as GasIndexTuning;
+use crate::{NoxTicks, VocTicks};
+use core::marker::PhantomData;
+
+/// Which gas index an [`GasIndexAlgorithm`] computes.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(u8)]
+pub enum GasIndexKind {
+    /// The volatile organic compounds (VOC) index.
+    Voc,
+    /// The nitrogen oxides (NOx) index.
+    Nox,
+}
+
+/// The learned state of a [`GasIndexAlgorithm`], which may be persisted and
+/// restored later to avoid repeating the learning phase.
+///
+/// Sensirion recommends that this state is only persisted after the algorithm
+/// has been running for at least three hours, and only restored if it was
+/// persisted no more than ten minutes ago.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct GasIndexState {
+    /// The estimated mean of the raw signal.
+    pub mean: f32,
+    /// The estimated standard deviation of the raw signal.
+    pub std: f32,
+}
+
+/// The Sensirion Gas Index Algorithm.
+///
+/// This is a fixed-step state machine: [`process`](Self::process) must be
+/// called once for every raw signal sample, at the sampling interval the
+/// algorithm was constructed with.
+///
+/// The kind of gas index computed is determined by the type of raw signal `S`
+/// that the algorithm processes: [`VocTicks`] for the VOC index, or
+/// [`NoxTicks`] for the NOx index. The [`VocIndexAlgorithm`] and
+/// [`NoxIndexAlgorithm`] aliases may be used to name each.
+///
+/// All of the algorithm's state is stored inline, so a copy of a
+/// `GasIndexAlgorithm` is a complete snapshot of the algorithm.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct GasIndexAlgorithm<S> {
+    algorithm: Algorithm,
+    _signal: PhantomData<fn(S)>,
+}
+
+/// A [`GasIndexAlgorithm`] computing the VOC index.
+pub type VocIndexAlgorithm = GasIndexAlgorithm<VocTicks>;
+
+/// A [`GasIndexAlgorithm`] computing the NOx index.
+pub type NoxIndexAlgorithm = GasIndexAlgorithm<NoxTicks>;
+
+/// A raw signal which may be processed by a [`GasIndexAlgorithm`].
+///
+/// This trait is sealed, and implemented for [`VocTicks`] and [`NoxTicks`].
+pub trait GasSignal: sealed::Sealed {
+    /// The kind of gas index computed from this signal.
+    const KIND: GasIndexKind;
+}
+
+mod sealed {
+    pub trait Sealed: Copy {
+        fn ticks(self) -> u16;
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+struct Algorithm {
+    kind: GasIndexKind,
+    sampling_interval: f32,
+    index_offset: f32,
+    sraw_minimum: i32,
+    gating_max_duration_minutes: f32,
+    init_duration_mean: f32,
+    init_duration_variance: f32,
+    gating_threshold: f32,
+    index_gain: f32,
+    tau_mean_hours: f32,
+    tau_variance_hours: f32,
+    sraw_std_initial: f32,
+    uptime: f32,
+    sraw: f32,
+    gas_index: f32,
+    estimator: MeanVarianceEstimator,
+    mox_model: MoxModel,
+    sigmoid_scaled: SigmoidScaled,
+    lowpass: AdaptiveLowpass,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+struct MeanVarianceEstimator {
+    initialized: bool,
+    mean: f32,
+    sraw_offset: f32,
+    std: f32,
+    gamma_mean: f32,
+    gamma_variance: f32,
+    gamma_initial_mean: f32,
+    gamma_initial_variance: f32,
+    current_gamma_mean: f32,
+    current_gamma_variance: f32,
+    uptime_gamma: f32,
+    uptime_gating: f32,
+    gating_duration_minutes: f32,
+    sigmoid: Sigmoid,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+struct Sigmoid {
+    k: f32,
+    x0: f32,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+struct MoxModel {
+    sraw_std: f32,
+    sraw_mean: f32,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+struct SigmoidScaled {
+    k: f32,
+    x0: f32,
+    offset_default: f32,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+struct AdaptiveLowpass {
+    a1: f32,
+    a2: f32,
+    initialized: bool,
+    x1: f32,
+    x2: f32,
+    x3: f32,
+}
+
+const DEFAULT_SAMPLING_INTERVAL: f32 = 1.0;
+const INITIAL_BLACKOUT: f32 = 45.0;
+const INDEX_GAIN: f32 = 230.0;
+const SRAW_STD_INITIAL: f32 = 50.0;
+const SRAW_STD_BONUS_VOC: f32 = 220.0;
+const SRAW_STD_NOX: f32 = 2000.0;
+const TAU_MEAN_HOURS: f32 = 12.0;
+const TAU_VARIANCE_HOURS: f32 = 12.0;
+const TAU_INITIAL_MEAN_VOC: f32 = 20.0;
+const TAU_INITIAL_MEAN_NOX: f32 = 1200.0;
+const INIT_DURATION_MEAN_VOC: f32 = 3600.0 * 0.75;
+const INIT_DURATION_MEAN_NOX: f32 = 3600.0 * 4.75;
+const INIT_TRANSITION_MEAN: f32 = 0.01;
+const TAU_INITIAL_VARIANCE: f32 = 2500.0;
+const INIT_DURATION_VARIANCE_VOC: f32 = 3600.0 * 1.45;
+const INIT_DURATION_VARIANCE_NOX: f32 = 3600.0 * 5.70;
+const INIT_TRANSITION_VARIANCE: f32 = 0.01;
+const GATING_THRESHOLD_VOC: f32 = 340.0;
+const GATING_THRESHOLD_NOX: f32 = 30.0;
+const GATING_THRESHOLD_INITIAL: f32 = 510.0;
+const GATING_THRESHOLD_TRANSITION: f32 = 0.09;
+const GATING_VOC_MAX_DURATION_MINUTES: f32 = 60.0 * 3.0;
+const GATING_NOX_MAX_DURATION_MINUTES: f32 = 60.0 * 12.0;
+const GATING_MAX_RATIO: f32 = 0.3;
+const SIGMOID_L: f32 = 500.0;
+const SIGMOID_K_VOC: f32 = -0.0065;
+const SIGMOID_X0_VOC: f32 = 213.0;
+const SIGMOID_K_NOX: f32 = -0.0101;
+const SIGMOID_X0_NOX: f32 = 614.0;
+const VOC_INDEX_OFFSET_DEFAULT: f32 = 100.0;
+const NOX_INDEX_OFFSET_DEFAULT: f32 = 1.0;
+const LP_TAU_FAST: f32 = 20.0;
+const LP_TAU_SLOW: f32 = 500.0;
+const LP_ALPHA: f32 = -0.2;
+const VOC_SRAW_MINIMUM: i32 = 20000;
+const NOX_SRAW_MINIMUM: i32 = 10000;
+const PERSISTENCE_UPTIME_GAMMA: f32 = 3.0 * 3600.0;
+const MVE_GAMMA_SCALING: f32 = 64.0;
+const MVE_ADDITIONAL_GAMMA_MEAN_SCALING: f32 = 8.0;
+const MVE_FIX16_MAX: f32 = 32767.0;
+
+// === impl GasIndexKind ===
+
+impl GasIndexKind {
+    /// Returns the default [`GasIndexTuning`] for this kind of gas index.
+    #[must_use]
+    pub const fn default_tuning(self) -> GasIndexTuning {
+        match self {
+            Self::Voc => GasIndexTuning::VOC_DEFAULT,
+            Self::Nox => GasIndexTuning::NOX_DEFAULT,
+        }
+    }
+}
+
+// === impl GasIndexAlgorithm ===
+
+impl<S: GasSignal> GasIndexAlgorithm<S> {
+    /// Returns a new algorithm with the default sampling interval of one
+    /// second.
+    ///
+    /// This is the rate at which the SEN5x produces new measurements.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_sampling_interval(DEFAULT_SAMPLING_INTERVAL)
+    }
+
+    /// Returns a new algorithm with the provided sampling interval in seconds.
+    ///
+    /// Sensirion has only tested the algorithm with sampling intervals of 1 and
+    /// 10 seconds; for the NOx index, the sampling interval must be 1 second.
+    #[must_use]
+    pub fn with_sampling_interval(sampling_interval: f32) -> Self {
+        Self {
+            algorithm: Algorithm::new(S::KIND, sampling_interval),
+            _signal: PhantomData,
+        }
+    }
+
+    /// Returns the kind of gas index computed by this algorithm.
+    #[must_use]
+    pub fn kind(&self) -> GasIndexKind {
+        S::KIND
+    }
+
+    /// Returns the sampling interval of this algorithm, in seconds.
+    #[must_use]
+    pub fn sampling_interval(&self) -> f32 {
+        self.algorithm.sampling_interval
+    }
+
+    /// Resets the algorithm's internal state, discarding anything it has
+    /// learned. The tuning parameters are retained.
+    pub fn reset(&mut self) {
+        self.algorithm.reset();
+    }
+
+    /// Returns the algorithm's current [`GasIndexTuning`] parameters.
+    #[must_use]
+    pub fn tuning(&self) -> GasIndexTuning {
+        self.algorithm.tuning()
+    }
+
+    /// Sets the algorithm's [`GasIndexTuning`] parameters.
+    ///
+    /// This resets the learned mean and variance estimates.
+    pub fn set_tuning(&mut self, tuning: GasIndexTuning) {
+        self.algorithm.set_tuning(tuning);
+    }
+
+    /// Returns the algorithm's learned [`GasIndexState`], for persisting.
+    #[must_use]
+    pub fn state(&self) -> GasIndexState {
+        self.algorithm.state()
+    }
+
+    /// Restores a previously persisted [`GasIndexState`].
+    pub fn set_state(&mut self, state: GasIndexState) {
+        self.algorithm.set_state(state);
+    }
+
+    /// Processes one raw signal sample, returning the gas index.
+    ///
+    /// The index is in range 1..=500. During the algorithm's initial blackout
+    /// period (45 seconds), the index is 0.
+    ///
+    /// Raw signal samples of 0 (or samples of 65000 and above) are considered
+    /// invalid, and the previous raw signal is reused.
+    pub fn process(&mut self, sraw: S) -> u16 {
+        self.algorithm.process(sraw.ticks())
+    }
+}
+
+impl<S: GasSignal> Default for GasIndexAlgorithm<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// === impl GasSignal ===
+
+impl GasSignal for VocTicks {
+    const KIND: GasIndexKind = GasIndexKind::Voc;
+}
+
+impl sealed::Sealed for VocTicks {
+    fn ticks(self) -> u16 {
+        self.0
+    }
+}
+
+impl GasSignal for NoxTicks {
+    const KIND: GasIndexKind = GasIndexKind::Nox;
+}
+
+impl sealed::Sealed for NoxTicks {
+    fn ticks(self) -> u16 {
+        self.0
+    }
+}
+
+// === impl Algorithm ===
+
+impl Algorithm {
+    fn new(kind: GasIndexKind, sampling_interval: f32) -> Self {
+        let (
+            index_offset,
+            sraw_minimum,
+            gating_max_duration_minutes,
+            init_duration_mean,
+            init_duration_variance,
+            gating_threshold,
+        ) = match kind {
+            GasIndexKind::Voc => (
+                VOC_INDEX_OFFSET_DEFAULT,
+                VOC_SRAW_MINIMUM,
+                GATING_VOC_MAX_DURATION_MINUTES,
+                INIT_DURATION_MEAN_VOC,
+                INIT_DURATION_VARIANCE_VOC,
+                GATING_THRESHOLD_VOC,
+            ),
+            GasIndexKind::Nox => (
+                NOX_INDEX_OFFSET_DEFAULT,
+                NOX_SRAW_MINIMUM,
+                GATING_NOX_MAX_DURATION_MINUTES,
+                INIT_DURATION_MEAN_NOX,
+                INIT_DURATION_VARIANCE_NOX,
+                GATING_THRESHOLD_NOX,
+            ),
+        };
+        let mut this = Self {
+            kind,
+            sampling_interval,
+            index_offset,
+            sraw_minimum,
+            gating_max_duration_minutes,
+            init_duration_mean,
+            init_duration_variance,
+            gating_threshold,
+            index_gain: INDEX_GAIN,
+            tau_mean_hours: TAU_MEAN_HOURS,
+            tau_variance_hours: TAU_VARIANCE_HOURS,
+            sraw_std_initial: SRAW_STD_INITIAL,
+            uptime: 0.0,
+            sraw: 0.0,
+            gas_index: 0.0,
+            estimator: MeanVarianceEstimator::UNINIT,
+            mox_model: MoxModel {
+                sraw_std: 0.0,
+                sraw_mean: 0.0,
+            },
+            sigmoid_scaled: SigmoidScaled {
+                k: 0.0,
+                x0: 0.0,
+                offset_default: 0.0,
+            },
+            lowpass: AdaptiveLowpass::UNINIT,
+        };
+        this.reset();
+        this
+    }
+
+    fn reset(&mut self) {
+        self.uptime = 0.0;
+        self.sraw = 0.0;
+        self.gas_index = 0.0;
+        self.init_instances();
+    }
+
+    fn tuning(&self) -> GasIndexTuning {
+        GasIndexTuning {
+            index_offset: self.index_offset as i16,
+            learning_time_offset_hours: self.tau_mean_hours as i16,
+            learning_time_gain_hours: self.tau_variance_hours as i16,
+            gating_max_duration_minutes: self.gating_max_duration_minutes as i16,
+            std_initial: self.sraw_std_initial as i16,
+            gain_factor: self.index_gain as i16,
+        }
+    }
+
+    fn set_tuning(&mut self, tuning: GasIndexTuning) {
+        self.index_offset = f32::from(tuning.index_offset);
+        self.tau_mean_hours = f32::from(tuning.learning_time_offset_hours);
+        self.tau_variance_hours = f32::from(tuning.learning_time_gain_hours);
+        self.gating_max_duration_minutes = f32::from(tuning.gating_max_duration_minutes);
+        self.sraw_std_initial = f32::from(tuning.std_initial);
+        self.index_gain = f32::from(tuning.gain_factor);
+        self.init_instances();
+    }
+
+    fn state(&self) -> GasIndexState {
+        GasIndexState {
+            mean: self.estimator.mean(),
+            std: self.estimator.std,
+        }
+    }
+
+    fn set_state(&mut self, state: GasIndexState) {
+        self.estimator
+            .set_states(state.mean, state.std, PERSISTENCE_UPTIME_GAMMA);
+        self.mox_model = MoxModel {
+            sraw_std: self.estimator.std,
+            sraw_mean: self.estimator.mean(),
+        };
+        self.sraw = state.mean;
+    }
+
+    fn process(&mut self, sraw: u16) -> u16 {
+        let sraw = i32::from(sraw);
+        if self.uptime <= INITIAL_BLACKOUT {
+            self.uptime += self.sampling_interval;
+        } else {
+            if sraw > 0 && sraw < 65000 {
+                let sraw = sraw.clamp(self.sraw_minimum + 1, self.sraw_minimum + 32767);
+                self.sraw = (sraw - self.sraw_minimum) as f32;
+            }
+            self.gas_index = if self.kind == GasIndexKind::Voc || self.estimator.initialized {
+                let index = self.mox_model.process(self, self.sraw);
+                self.sigmoid_scaled.process(self.index_offset, index)
+            } else {
+                self.index_offset
+            };
+            self.gas_index = self.lowpass.process(self.sampling_interval, self.gas_index);
+            if self.gas_index < 0.5 {
+                self.gas_index = 0.5;
+            }
+            if self.sraw > 0.0 {
+                let mut estimator = self.estimator;
+                estimator.process(self, self.sraw);
+                self.estimator = estimator;
+                self.mox_model = MoxModel {
+                    sraw_std: self.estimator.std,
+                    sraw_mean: self.estimator.mean(),
+                };
+            }
+        }
+        (self.gas_index + 0.5) as u16
+    }
+
+    fn init_instances(&mut self) {
+        self.estimator = MeanVarianceEstimator::new(self);
+        self.mox_model = MoxModel {
+            sraw_std: self.estimator.std,
+            sraw_mean: self.estimator.mean(),
+        };
+        self.sigmoid_scaled = match self.kind {
+            GasIndexKind::Voc => SigmoidScaled {
+                k: SIGMOID_K_VOC,
+                x0: SIGMOID_X0_VOC,
+                offset_default: VOC_INDEX_OFFSET_DEFAULT,
+            },
+            GasIndexKind::Nox => SigmoidScaled {
+                k: SIGMOID_K_NOX,
+                x0: SIGMOID_X0_NOX,
+                offset_default: NOX_INDEX_OFFSET_DEFAULT,
+            },
+        };
+        self.lowpass = AdaptiveLowpass::new(self.sampling_interval);
+    }
+}
+
+// === impl MeanVarianceEstimator ===
+
+impl MeanVarianceEstimator {
+    const UNINIT: Self = Self {
+        initialized: false,
+        mean: 0.0,
+        sraw_offset: 0.0,
+        std: 0.0,
+        gamma_mean: 0.0,
+        gamma_variance: 0.0,
+        gamma_initial_mean: 0.0,
+        gamma_initial_variance: 0.0,
+        current_gamma_mean: 0.0,
+        current_gamma_variance: 0.0,
+        uptime_gamma: 0.0,
+        uptime_gating: 0.0,
+        gating_duration_minutes: 0.0,
+        sigmoid: Sigmoid { k: 0.0, x0: 0.0 },
+    };
+
+    fn new(params: &Algorithm) -> Self {
+        let interval = params.sampling_interval;
+        let interval_hours = interval / 3600.0;
+        let tau_initial_mean = match params.kind {
+            GasIndexKind::Voc => TAU_INITIAL_MEAN_VOC,
+            GasIndexKind::Nox => TAU_INITIAL_MEAN_NOX,
+        };
+        Self {
+            std: params.sraw_std_initial,
+            gamma_mean: ((MVE_ADDITIONAL_GAMMA_MEAN_SCALING * MVE_GAMMA_SCALING) * interval_hours)
+                / (params.tau_mean_hours + interval_hours),
+            gamma_variance: (MVE_GAMMA_SCALING * interval_hours)
+                / (params.tau_variance_hours + interval_hours),
+            gamma_initial_mean: ((MVE_ADDITIONAL_GAMMA_MEAN_SCALING * MVE_GAMMA_SCALING)
+                * interval)
+                / (tau_initial_mean + interval),
+            gamma_initial_variance: (MVE_GAMMA_SCALING * interval)
+                / (TAU_INITIAL_VARIANCE + interval),
+            ..Self::UNINIT
+        }
+    }
+
+    fn set_states(&mut self, mean: f32, std: f32, uptime_gamma: f32) {
+        self.mean = mean;
+        self.std = std;
+        self.uptime_gamma = uptime_gamma;
+        self.initialized = true;
+    }
+
+    fn mean(&self) -> f32 {
+        self.mean + self.sraw_offset
+    }
+
+    fn calculate_gamma(&mut self, params: &Algorithm) {
+        let interval = params.sampling_interval;
+        let uptime_limit = MVE_FIX16_MAX - interval;
+        if self.uptime_gamma < uptime_limit {
+            self.uptime_gamma += interval;
+        }
+        if self.uptime_gating < uptime_limit {
+            self.uptime_gating += interval;
+        }
+
+        self.sigmoid = Sigmoid {
+            x0: params.init_duration_mean,
+            k: INIT_TRANSITION_MEAN,
+        };
+        let sigmoid_gamma_mean = self.sigmoid.process(self.uptime_gamma);
+        let gamma_mean =
+            self.gamma_mean + (self.gamma_initial_mean - self.gamma_mean) * sigmoid_gamma_mean;
+        let gating_threshold_mean = params.gating_threshold
+            + (GATING_THRESHOLD_INITIAL - params.gating_threshold)
+                * self.sigmoid.process(self.uptime_gating);
+        self.sigmoid = Sigmoid {
+            x0: gating_threshold_mean,
+            k: GATING_THRESHOLD_TRANSITION,
+        };
+        let sigmoid_gating_mean = self.sigmoid.process(params.gas_index);
+        self.current_gamma_mean = sigmoid_gating_mean * gamma_mean;
+
+        self.sigmoid = Sigmoid {
+            x0: params.init_duration_variance,
+            k: INIT_TRANSITION_VARIANCE,
+        };
+        let sigmoid_gamma_variance = self.sigmoid.process(self.uptime_gamma);
+        let gamma_variance = self.gamma_variance
+            + (self.gamma_initial_variance - self.gamma_variance)
+                * (sigmoid_gamma_variance - sigmoid_gamma_mean);
+        let gating_threshold_variance = params.gating_threshold
+            + (GATING_THRESHOLD_INITIAL - params.gating_threshold)
+                * self.sigmoid.process(self.uptime_gating);
+        self.sigmoid = Sigmoid {
+            x0: gating_threshold_variance,
+            k: GATING_THRESHOLD_TRANSITION,
+        };
+        let sigmoid_gating_variance = self.sigmoid.process(params.gas_index);
+        self.current_gamma_variance = sigmoid_gating_variance * gamma_variance;
+
+        self.gating_duration_minutes += (interval / 60.0)
+            * (((1.0 - sigmoid_gating_mean) * (1.0 + GATING_MAX_RATIO)) - GATING_MAX_RATIO);
+        if self.gating_duration_minutes < 0.0 {
+            self.gating_duration_minutes = 0.0;
+        }
+        if self.gating_duration_minutes > params.gating_max_duration_minutes {
+            self.uptime_gating = 0.0;
+        }
+    }
+
+    fn process(&mut self, params: &Algorithm, sraw: f32) {
+        if !self.initialized {
+            self.initialized = true;
+            self.sraw_offset = sraw;
+            self.mean = 0.0;
+            return;
+        }
+
+        if self.mean >= 100.0 || self.mean <= -100.0 {
+            self.sraw_offset += self.mean;
+            self.mean = 0.0;
+        }
+        let sraw = sraw - self.sraw_offset;
+        self.calculate_gamma(params);
+        let delta_sgp = (sraw - self.mean) / MVE_GAMMA_SCALING;
+        let c = if delta_sgp < 0.0 {
+            self.std - delta_sgp
+        } else {
+            self.std + delta_sgp
+        };
+        let additional_scaling = if c > 1440.0 {
+            (c / 1440.0) * (c / 1440.0)
+        } else {
+            1.0
+        };
+        self.std =
+            libm::sqrtf(additional_scaling * (MVE_GAMMA_SCALING - self.current_gamma_variance))
+                * libm::sqrtf(
+                    (self.std * (self.std / (MVE_GAMMA_SCALING * additional_scaling)))
+                        + (((self.current_gamma_variance * delta_sgp) / additional_scaling)
+                            * delta_sgp),
+                );
+        self.mean += (self.current_gamma_mean * delta_sgp) / MVE_ADDITIONAL_GAMMA_MEAN_SCALING;
+    }
+}
+
+// === impl Sigmoid ===
+
+impl Sigmoid {
+    fn process(&self, sample: f32) -> f32 {
+        let x = self.k * (sample - self.x0);
+        if x < -50.0 {
+            1.0
+        } else if x > 50.0 {
+            0.0
+        } else {
+            1.0 / (1.0 + libm::expf(x))
+        }
+    }
+}
+
+// === impl MoxModel ===
+
+impl MoxModel {
+    fn process(&self, params: &Algorithm, sraw: f32) -> f32 {
+        match params.kind {
+            GasIndexKind::Voc => {
+                ((sraw - self.sraw_mean) / -(self.sraw_std + SRAW_STD_BONUS_VOC))
+                    * params.index_gain
+            }
+            GasIndexKind::Nox => ((sraw - self.sraw_mean) / SRAW_STD_NOX) * params.index_gain,
+        }
+    }
+}
+
+// === impl SigmoidScaled ===
+
+impl SigmoidScaled {
+    fn process(&self, index_offset: f32, sample: f32) -> f32 {
+        let x = self.k * (sample - self.x0);
+        if x < -50.0 {
+            return SIGMOID_L;
+        }
+        if x > 50.0 {
+            return 0.0;
+        }
+
+        if sample >= 0.0 {
+            let shift = if self.offset_default == 1.0 {
+                (500.0 / 499.0) * (1.0 - index_offset)
+            } else {
+                (SIGMOID_L - (5.0 * index_offset)) / 4.0
+            };
+            ((SIGMOID_L + shift) / (1.0 + libm::expf(x))) - shift
+        } else {
+            (index_offset / self.offset_default) * (SIGMOID_L / (1.0 + libm::expf(x)))
+        }
+    }
+}
+
+// === impl AdaptiveLowpass ===
+
+impl AdaptiveLowpass {
+    const UNINIT: Self = Self {
+        a1: 0.0,
+        a2: 0.0,
+        initialized: false,
+        x1: 0.0,
+        x2: 0.0,
+        x3: 0.0,
+    };
+
+    fn new(interval: f32) -> Self {
+        Self {
+            a1: interval / (LP_TAU_FAST + interval),
+            a2: interval / (LP_TAU_SLOW + interval),
+            ..Self::UNINIT
+        }
+    }
+
+    fn process(&mut self, interval: f32, sample: f32) -> f32 {
+        if !self.initialized {
+            self.x1 = sample;
+            self.x2 = sample;
+            self.x3 = sample;
+            self.initialized = true;
+        }
+        self.x1 = (1.0 - self.a1) * self.x1 + self.a1 * sample;
+        self.x2 = (1.0 - self.a2) * self.x2 + self.a2 * sample;
+        let abs_delta = (self.x1 - self.x2).abs();
+        let f1 = libm::expf(LP_ALPHA * abs_delta);
+        let tau_a = (LP_TAU_SLOW - LP_TAU_FAST) * f1 + LP_TAU_FAST;
+        let a3 = interval / (interval + tau_a);
+        self.x3 = (1.0 - a3) * self.x3 + a3 * sample;
+        self.x3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A raw signal around `baseline`, with a small repeating ripple, a drop of
+    /// 4000 ticks from samples 3000 to 3299, and a rise of 3000 ticks from
+    /// samples 6000 to 6099.
+    fn signal(baseline: i32, i: u32) -> u16 {
+        let t = i as i32;
+        let event = match t {
+            3000..=3299 => -4000,
+            6000..=6099 => 3000,
+            _ => 0,
+        };
+        (baseline + (t * 37) % 200 - 100 + event) as u16
+    }
+
+    #[test]
+    fn default_tuning_matches_algorithm() {
+        // The tuning type is shared with the sensor's commands, so its
+        // defaults must match the algorithm's constants.
+        assert!(GasIndexAlgorithm::<VocTicks>::new().tuning() == GasIndexTuning::VOC_DEFAULT);
+        assert!(GasIndexAlgorithm::<NoxTicks>::new().tuning() == GasIndexTuning::NOX_DEFAULT);
+        assert!(GasIndexKind::Voc.default_tuning() == GasIndexTuning::VOC_DEFAULT);
+        assert!(GasIndexKind::Nox.default_tuning() == GasIndexTuning::NOX_DEFAULT);
+    }
+
+    /// Runs the algorithm over `signal()`, checking the index at each sample
+    /// in `expected`.
+    ///
+    /// The expected indices were produced by an independent port of version
+    /// 3.2.0 of Sensirion's C reference implementation.
+    fn check<S: GasSignal>(ticks: fn(u16) -> S, baseline: i32, expected: &[(u32, u16)]) {
+        let mut algorithm = GasIndexAlgorithm::<S>::new();
+        let mut expected = expected.iter().peekable();
+        for i in 0..15_000 {
+            let index = algorithm.process(ticks(signal(baseline, i)));
+            if let Some(&(_, want)) = expected.next_if(|(at, _)| *at == i) {
+                assert_eq!(index, want, "index at sample {i}");
+            }
+        }
+        assert!(expected.next().is_none());
+    }
+
+    #[test]
+    fn voc_reference_vectors() {
+        check(
+            VocTicks,
+            30_000,
+            &[
+                (0, 0),
+                (44, 0),
+                (45, 0),
+                (46, 1),
+                (60, 41),
+                (100, 95),
+                (300, 104),
+                (1000, 105),
+                (2999, 103),
+                (3005, 197),
+                (3030, 411),
+                (3100, 494),
+                (3299, 468),
+                (3320, 247),
+                (3600, 81),
+                (5999, 76),
+                (6005, 69),
+                (6030, 22),
+                (6099, 2),
+                (6120, 46),
+                (7000, 78),
+                (10000, 78),
+                (14999, 80),
+            ],
+        );
+    }
+
+    #[test]
+    fn nox_reference_vectors() {
+        check(
+            NoxTicks,
+            16_000,
+            &[
+                (0, 0),
+                (44, 0),
+                (45, 0),
+                (46, 1),
+                (60, 1),
+                (100, 1),
+                (300, 1),
+                (1000, 1),
+                (2999, 1),
+                (3005, 1),
+                (3030, 1),
+                (3100, 1),
+                (3299, 1),
+                (3320, 1),
+                (3600, 2),
+                (5999, 1),
+                (6005, 2),
+                (6030, 17),
+                (6099, 27),
+                (6120, 20),
+                (7000, 2),
+                (10000, 1),
+                (14999, 1),
+            ],
+        );
+    }
+
+    #[test]
+    fn constant_signal_settles_at_index_offset() {
+        let mut voc = VocIndexAlgorithm::new();
+        let mut nox = NoxIndexAlgorithm::new();
+        for _ in 0..200 {
+            voc.process(VocTicks(1337));
+            nox.process(NoxTicks(1337));
+        }
+        assert_eq!(voc.process(VocTicks(1337)), 100);
+        assert_eq!(nox.process(NoxTicks(1337)), 1);
+    }
+}
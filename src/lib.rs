@@ -1,15 +1,66 @@
 #![no_std]
 
-#[cfg(feature = "embedded-hal-async")]
-mod asynchronous;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod clock;
 mod cmd;
+#[cfg(feature = "float")]
+mod compensation;
+mod config;
+mod diagnostics;
+mod driver;
+mod duty_cycle;
+mod fan_cleaning;
+#[cfg(feature = "float")]
+pub mod gas_index;
 mod msg;
+mod mux;
+mod pending;
+mod power;
+mod retry;
+mod sanity;
+mod stability;
+mod state;
+#[cfg(feature = "float")]
+mod stats;
+mod supervisor;
+mod units;
+#[cfg(feature = "embassy-time")]
+pub use self::clock::EmbassyClock;
+#[cfg(any(target_has_atomic = "32", feature = "critical-section"))]
+pub use self::clock::MonotonicCounter;
+#[cfg(feature = "std")]
+pub use self::clock::StdClock;
+pub use self::clock::{Clock, Instant, OptionalClock, TimestampedMeasurements};
+#[cfg(feature = "float")]
+pub use self::compensation::{Compensated, Compensator, ThermalModel};
+pub use self::config::Sen5xConfig;
+pub use self::diagnostics::{CrcErrors, Diagnostics};
+pub use self::duty_cycle::DutyCycle;
+pub use self::fan_cleaning::FanCleaningSchedule;
 pub use msg::*;
+pub use mux::MuxChannel;
+pub use pending::{CommandOutput, PendingCommand};
+pub use retry::{RetryPolicy, RetryStats};
+pub use sanity::{Anomalies, SanityChecker};
+pub use stability::{ChannelStability, Stability};
+pub use state::State;
+#[cfg(feature = "float")]
+pub use stats::{ChannelStats, MeasurementStats};
+pub use supervisor::{Health, RecoveryAction, RecoveryStats, Supervisor};
+#[cfg(feature = "units")]
+pub use units::MassConcentration;
+pub use units::{
+    Celsius, CompensatedCelsius, CompensatedPercent, Fixed, NoxTicks, Percent, RawCelsius,
+    RawPercent, VocTicks,
+};
 
 const I2C_ADDR: u8 = 0x69; // nice!
 
+pub use self::driver::{DutyCycleIter, MeasurementIter, Sen5x, Sen5xArray};
 #[cfg(feature = "embedded-hal-async")]
-pub use self::asynchronous::Sen5xAsync;
+pub use self::driver::{DutyCycleStream, MeasurementStream, Sen5xArrayAsync, Sen5xAsync};
 
 pub enum Error<E> {
     /// An I<sup>2</sup>C error occurred during a write operation.
@@ -21,6 +72,34 @@ pub enum Error<E> {
     /// The requested operation can only be performed when the sensor is in the
     /// provided mode.
     WrongMode(Mode),
+    /// The sensor did not become ready before a timeout elapsed.
+    Timeout,
+    /// The sensor reported an error in its device status register.
+    DeviceStatus(SensorStatus),
+    /// The sensor was considered to have failed by a
+    /// [`Supervisor`], after every recovery action was exhausted.
+    SensorFailed,
+    /// The device did not identify itself as a SEN50, SEN54, or SEN55.
+    UnknownDevice,
+    /// The pin controlling the sensor's power could not be driven.
+    PowerPin,
+    /// A command could not be sent without waiting, because the sensor is
+    /// still executing the previous command.
+    Busy,
+}
+
+/// A single measurement cycle read from the sensor.
+///
+/// This is yielded by [`MeasurementIter`] and
+/// [`MeasurementStream`].
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct Sample {
+    /// The measurements read from the sensor.
+    pub measurements: Measurements,
+    /// The raw signals read in the same measurement cycle, if the iterator or
+    /// stream was configured to read them.
+    pub raw_signals: Option<RawSignals>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -62,6 +141,41 @@ impl<E: core::fmt::Display> core::fmt::Display for Error<E> {
                 f,
                 "this operation can only be performed when the sensor is in the {mode:?} mode"
             ),
+            Self::Timeout => f.write_str("timed out waiting for the sensor"),
+            Self::DeviceStatus(status) => {
+                write!(f, "the sensor reported an error (status: {status:?})")
+            }
+            Self::SensorFailed => f.write_str("the sensor failed and could not be recovered"),
+            Self::UnknownDevice => f.write_str("the device is not a SEN5x sensor"),
+            Self::PowerPin => f.write_str("failed to drive the sensor's power pin"),
+            Self::Busy => f.write_str("the sensor is still executing the previous command"),
+        }
+    }
+}
+
+impl<E: embedded_hal::i2c::Error> Error<E> {
+    /// Returns `true` if this error is likely to be transient, and the failed
+    /// operation may succeed if it is retried.
+    ///
+    /// CRC mismatches and I²C errors caused by the bus (such as a missing
+    /// acknowledgement or lost arbitration) are retriable. Errors which will
+    /// recur on every attempt, such as [`Error::WrongMode`], are not.
+    #[must_use]
+    pub fn is_retriable(&self) -> bool {
+        use embedded_hal::i2c::ErrorKind;
+        match self {
+            Self::I2cWrite(e) | Self::I2cRead(e) => matches!(
+                e.kind(),
+                ErrorKind::NoAcknowledge(_) | ErrorKind::ArbitrationLoss | ErrorKind::Bus
+            ),
+            Self::Decode(DecodeError::Crc) | Self::Busy => true,
+            Self::Decode(DecodeError::Msg(_))
+            | Self::WrongMode(_)
+            | Self::Timeout
+            | Self::DeviceStatus(_)
+            | Self::SensorFailed
+            | Self::UnknownDevice
+            | Self::PowerPin => false,
         }
     }
 }
@@ -1,12 +1,32 @@
+#[cfg(feature = "units")]
+use crate::units::MassConcentration;
+#[cfg(feature = "units")]
+use crate::units::{Celsius, Percent};
+use crate::units::{Fixed, NoxTicks, VocTicks};
+#[cfg(feature = "float")]
+use crate::units::{RawCelsius, RawPercent};
+use crate::{ChannelStability, ParticulateMode};
 use sensirion_i2c::crc8;
 
-pub(crate) trait Decode: Sized {
+/// A message which can be decoded from the sensor's on-the-wire
+/// representation.
+pub trait Decode: Sized {
+    /// The buffer containing the message's on-the-wire representation,
+    /// including CRC8 checksums.
     type Buf;
+
+    /// Decodes a message from `buf`, validating its checksums.
     fn decode(buf: &Self::Buf) -> Result<Self, DecodeError>;
 }
 
-pub(crate) trait Encode: Sized {
-    fn encode(self, buf: &mut [u8]);
+/// A message which can be encoded to the sensor's on-the-wire representation.
+///
+/// This is the inverse of [`Decode`]: encoding a message and decoding the
+/// resulting buffer produces the original message.
+pub trait Encode: Decode {
+    /// Encodes this message into `buf`, including a CRC8 checksum for each
+    /// word.
+    fn encode(&self, buf: &mut Self::Buf);
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -25,7 +45,12 @@ pub struct MessageError {
     _p: (),
 }
 
-pub(crate) struct DataReady(pub(crate) bool);
+/// A response to the "Read Data-Ready Flag" command.
+///
+/// The flag is `true` if a new measurement is available to be read.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct DataReady(pub bool);
 
 /// Sensor version information.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -47,7 +72,89 @@ pub struct Version {
     pub minor: u8,
 }
 
+/// Temperature offset compensation parameters.
+///
+/// The sensor compensates its temperature (and humidity) readings for heat
+/// from the device it is installed in, using an offset of:
+///
+/// ```text
+/// offset + slope * temperature
+/// ```
+///
+/// which is applied with a first-order time constant.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct TemperatureOffset {
+    /// The constant temperature offset, in degrees Celsius.
+    pub offset: Fixed<i16, TEMP_SCALE>,
+    /// The normalized temperature offset slope.
+    pub slope: Fixed<i16, SLOPE_SCALE>,
+    /// The time constant, in seconds, with which changes to the offset are
+    /// applied. A value of 0 applies changes immediately.
+    pub time_constant_s: u16,
+}
+
+/// Tuning parameters for the VOC or NOx gas index algorithm.
+///
+/// These are the parameters accepted by the SEN5x's "Set VOC Algorithm Tuning
+/// Parameters" and "Set NOx Algorithm Tuning Parameters" commands, and by the
+/// host-side port of the algorithm in the `gas_index` module, where this type
+/// is also available as `GasIndexTuning`. See Sensirion's [engineering
+/// guidelines for the SEN5x][guide] for details. The default values are
+/// [`AlgorithmTuning::VOC_DEFAULT`] and [`AlgorithmTuning::NOX_DEFAULT`].
+///
+/// [guide]: https://sensirion.com/media/documents/25AB572C/62B463AA/Sensirion_Engineering_Guidelines_SEN5x.pdf
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct AlgorithmTuning {
+    /// The gas index representing typical (average) conditions.
+    ///
+    /// Allowed values are in range 1..=250.
+    pub index_offset: i16,
+    /// Time constant, in hours, to estimate the mean of the raw signal.
+    ///
+    /// Allowed values are in range 1..=1000.
+    pub learning_time_offset_hours: i16,
+    /// Time constant, in hours, to estimate the variance of the raw signal.
+    ///
+    /// Allowed values are in range 1..=1000. This parameter has no effect for
+    /// the NOx index.
+    pub learning_time_gain_hours: i16,
+    /// Maximum duration, in minutes, during which the estimator is frozen when
+    /// a high gas index signal is read. Zero disables the gating.
+    ///
+    /// Allowed values are in range 0..=3000.
+    pub gating_max_duration_minutes: i16,
+    /// Initial estimate for the standard deviation of the raw signal.
+    ///
+    /// Allowed values are in range 10..=5000. This parameter has no effect for
+    /// the NOx index, and must be set to 50.
+    pub std_initial: i16,
+    /// Gain factor to amplify or attenuate the gas index output.
+    ///
+    /// Allowed values are in range 1..=1000.
+    pub gain_factor: i16,
+}
+
+/// The sensor's temperature and humidity acceleration mode.
+///
+/// This controls how quickly the sensor's temperature and humidity readings
+/// respond to changes, depending on the design of the device it is installed
+/// in.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(u16)]
+pub enum RhtAccelerationMode {
+    /// Low acceleration, the default.
+    Low = 0,
+    /// High acceleration.
+    High = 1,
+    /// Medium acceleration.
+    Medium = 2,
+}
+
 /// A raw string in the device's representation.
+#[derive(Clone, PartialEq, Eq)]
 pub struct RawString {
     bytes: [u8; Self::LEN],
     len: usize,
@@ -75,6 +182,21 @@ pub struct RawString {
 /// | 20     | CRC8 |              |                                         |
 /// | 21..22 | i16  | 10           | NOx Index                               |
 /// | 23     | CRC8 |              |                                         |
+///
+/// A value of `0xFFFF` (for unsigned values) or `0x7FFF` (for signed values)
+/// indicates that a reading is not present.
+///
+/// In addition to being read from the sensor, `Measurements` may be constructed
+/// using [`Measurements::builder()`] or [`Measurements::from_raw_words()`].
+///
+/// Two `Measurements` are equal if their readings are equal. The annotations
+/// set by the drivers ([`pm_disabled_by_mode()`](Self::pm_disabled_by_mode),
+/// [`stability()`](Self::stability), and
+/// [`during_fan_cleaning()`](Self::during_fan_cleaning)) are not sent on the
+/// wire, and are not compared, so encoding and decoding a `Measurements`
+/// produces an equal value.
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
 pub struct Measurements {
     pm1_0: Option<u16>,
     pm2_5: Option<u16>,
@@ -84,6 +206,31 @@ pub struct Measurements {
     temp: Option<i16>,
     voc: Option<i16>,
     nox: Option<i16>,
+    pm_disabled: bool,
+    stability: ChannelStability,
+    fan_cleaning: bool,
+}
+
+/// A measurement channel of the sensor.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub enum Channel {
+    /// PM<sub>1.0</sub> concentration, in µg/m³.
+    Pm1_0,
+    /// PM<sub>2.5</sub> concentration, in µg/m³.
+    Pm2_5,
+    /// PM<sub>4.0</sub> concentration, in µg/m³.
+    Pm4_0,
+    /// PM<sub>10.0</sub> concentration, in µg/m³.
+    Pm10_0,
+    /// Relative humidity, in %RH.
+    RelativeHumidity,
+    /// Temperature, in degrees Celsius.
+    Temperature,
+    /// VOC index.
+    VocIndex,
+    /// NOx index.
+    NoxIndex,
 }
 
 /// Raw measurement signals.
@@ -92,6 +239,8 @@ pub struct Measurements {
 /// details on these values.
 ///
 /// [appnote]: https://sensirion.com/media/documents/2B6FC1F3/649C3D0E/PS_AN_Read_RHT_VOC_and_NOx_RAW_signals_v2_D1.pdf
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
 pub struct RawSignals {
     humidity: Option<i16>,
     temp: Option<i16>,
@@ -99,7 +248,21 @@ pub struct RawSignals {
     nox: Option<u16>,
 }
 
+/// A builder for constructing [`Measurements`].
+///
+/// This is returned by [`Measurements::builder()`]. Any readings which are not
+/// set will not be present in the built [`Measurements`].
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[must_use]
+pub struct MeasurementsBuilder {
+    measurements: Measurements,
+}
+
 bitflags::bitflags! {
+    /// The sensor's device status register.
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "fmt", derive(Debug))]
     pub struct SensorStatus: u32 {
         /// `FAN`: Fan failure, fan is mechanically blocked or broken.
         ///
@@ -146,12 +309,12 @@ bitflags::bitflags! {
 
 impl DecodeError {
     #[cfg(feature = "fmt")]
-    fn msg(msg: &'static str) -> Self {
+    pub(crate) fn msg(msg: &'static str) -> Self {
         Self::Msg(MessageError { msg })
     }
 
     #[cfg(not(feature = "fmt"))]
-    fn msg(_: &'static str) -> Self {
+    pub(crate) fn msg(_: &'static str) -> Self {
         Self::Msg(MessageError { _p: () })
     }
 }
@@ -212,6 +375,19 @@ impl Decode for DataReady {
     }
 }
 
+impl Encode for DataReady {
+    fn encode(&self, buf: &mut Self::Buf) {
+        put_word(buf, 0, u16::from(self.0));
+    }
+}
+
+/// Writes a word and its CRC8 checksum to `buf`, starting at `idx`.
+fn put_word(buf: &mut [u8], idx: usize, word: u16) {
+    let bytes = word.to_be_bytes();
+    buf[idx..idx + 2].copy_from_slice(&bytes);
+    buf[idx + 2] = crc8::calculate(&bytes);
+}
+
 macro_rules! word {
     ($buf:ident[$idx:expr]) => {
         word!($buf[$idx] as u16)
@@ -228,12 +404,54 @@ macro_rules! word {
     }};
 }
 
+#[cfg(feature = "float")]
 macro_rules! scale_float {
     ($field:expr, $scale:expr) => {
-        $field.map(|v| v as f32 / $scale)
+        $field.map(|v| f32::from(v) / f32::from($scale))
     };
 }
 
+/// Scale factor of particulate matter concentrations on the wire.
+const PM_SCALE: u16 = 10;
+/// Scale factor of relative humidity on the wire.
+const RH_SCALE: u16 = 100;
+/// Scale factor of temperature on the wire.
+const TEMP_SCALE: u16 = 200;
+/// Scale factor of VOC and NOx indices on the wire.
+const INDEX_SCALE: u16 = 10;
+/// Scale factor of the temperature offset slope on the wire.
+const SLOPE_SCALE: u16 = 10000;
+
+/// Returns `Some(raw)` unless `raw` is the "not present" value for an unsigned
+/// word.
+const fn present_u16(raw: u16) -> Option<u16> {
+    match raw {
+        u16::MAX => None,
+        x => Some(x),
+    }
+}
+
+/// Returns `Some(raw)` unless `raw` is the "not present" value for a signed
+/// word.
+const fn present_i16(raw: i16) -> Option<i16> {
+    match raw {
+        i16::MAX => None,
+        x => Some(x),
+    }
+}
+
+/// Scales a floating-point value to its on-the-wire representation, rounding
+/// to the nearest representable value. The "not present" value is never
+/// returned.
+#[cfg(feature = "float")]
+macro_rules! unscale_float {
+    ($value:expr, $scale:expr, $T:ty) => {{
+        let raw = libm::roundf($value * f32::from($scale));
+        // Float-to-int `as` casts saturate, and NaN becomes 0.
+        (raw as $T).min(<$T>::MAX - 1)
+    }};
+}
+
 // === impl Measurements ===
 
 impl Decode for Measurements {
@@ -248,37 +466,195 @@ impl Decode for Measurements {
             temp: word!(buf[15] as i16),
             voc: word!(buf[18] as i16),
             nox: word!(buf[21] as i16),
+            pm_disabled: false,
+            stability: ChannelStability::STABLE,
+            fan_cleaning: false,
         })
     }
 }
 
+impl Encode for Measurements {
+    fn encode(&self, buf: &mut Self::Buf) {
+        for (i, word) in self.to_raw_words().into_iter().enumerate() {
+            put_word(buf, i * 3, word);
+        }
+    }
+}
+
+impl PartialEq for Measurements {
+    fn eq(&self, other: &Self) -> bool {
+        // Only the readings are compared, not the annotations set by the
+        // drivers.
+        self.to_raw_words() == other.to_raw_words()
+    }
+}
+
+impl Eq for Measurements {}
+
+impl Measurements {
+    /// Returns a [`MeasurementsBuilder`] for constructing a `Measurements`.
+    pub const fn builder() -> MeasurementsBuilder {
+        MeasurementsBuilder {
+            measurements: Self {
+                pm1_0: None,
+                pm2_5: None,
+                pm4_0: None,
+                pm10_0: None,
+                rh: None,
+                temp: None,
+                voc: None,
+                nox: None,
+                pm_disabled: false,
+                stability: ChannelStability::STABLE,
+                fan_cleaning: false,
+            },
+        }
+    }
+
+    /// Constructs a `Measurements` from raw words, in the order in which they
+    /// are sent on the wire (excluding CRCs).
+    ///
+    /// The words are PM<sub>1.0</sub>, PM<sub>2.5</sub>, PM<sub>4.0</sub>,
+    /// PM<sub>10.0</sub>, relative humidity, temperature, VOC index, and NOx
+    /// index. The last four are interpreted as signed (two's complement)
+    /// values. See the [type-level documentation](Self) for scale factors.
+    #[must_use]
+    pub const fn from_raw_words(words: [u16; 8]) -> Self {
+        Self {
+            pm1_0: present_u16(words[0]),
+            pm2_5: present_u16(words[1]),
+            pm4_0: present_u16(words[2]),
+            pm10_0: present_u16(words[3]),
+            rh: present_i16(words[4] as i16),
+            temp: present_i16(words[5] as i16),
+            voc: present_i16(words[6] as i16),
+            nox: present_i16(words[7] as i16),
+            pm_disabled: false,
+            stability: ChannelStability::STABLE,
+            fan_cleaning: false,
+        }
+    }
+
+    /// Returns the raw words of this `Measurements`, in the order in which
+    /// they are sent on the wire (excluding CRCs).
+    ///
+    /// This is the inverse of [`from_raw_words()`](Self::from_raw_words).
+    #[must_use]
+    pub const fn to_raw_words(&self) -> [u16; 8] {
+        const fn u(word: Option<u16>) -> u16 {
+            match word {
+                Some(x) => x,
+                None => u16::MAX,
+            }
+        }
+        const fn i(word: Option<i16>) -> u16 {
+            match word {
+                Some(x) => x as u16,
+                None => i16::MAX as u16,
+            }
+        }
+        [
+            u(self.pm1_0),
+            u(self.pm2_5),
+            u(self.pm4_0),
+            u(self.pm10_0),
+            i(self.rh),
+            i(self.temp),
+            i(self.voc),
+            i(self.nox),
+        ]
+    }
+
+    /// Returns `true` if these measurements were taken while the sensor was
+    /// measuring with particulate matter disabled
+    /// ([`ParticulateMode::Disabled`]).
+    ///
+    /// In that mode, the particulate matter readings are absent because the
+    /// sensor was not measuring them, rather than because of an error with the
+    /// particulate matter sensor. This is set by the drivers when reading
+    /// measurements; measurements decoded without a driver never have it
+    /// set.
+    #[inline]
+    #[must_use]
+    pub const fn pm_disabled_by_mode(&self) -> bool {
+        self.pm_disabled
+    }
+
+    /// Records the [`ParticulateMode`] the sensor was
+    /// measuring in when these measurements were read.
+    #[must_use]
+    pub(crate) const fn with_particulate_mode(mut self, mode: ParticulateMode) -> Self {
+        self.pm_disabled = matches!(mode, ParticulateMode::Disabled);
+        self
+    }
+
+    /// Returns the [`ChannelStability`] of these measurements.
+    ///
+    /// This is set by the drivers when reading measurements, based on how long
+    /// the sensor had been measuring. Measurements decoded without a driver
+    /// are always [stable](ChannelStability::STABLE).
+    #[inline]
+    #[must_use]
+    pub const fn stability(&self) -> ChannelStability {
+        self.stability
+    }
+
+    #[must_use]
+    pub(crate) const fn with_stability(mut self, stability: ChannelStability) -> Self {
+        self.stability = stability;
+        self
+    }
+
+    /// Returns `true` if these measurements were taken while the sensor's fan
+    /// cleaning procedure was running.
+    ///
+    /// Particulate matter readings taken during fan cleaning are not
+    /// meaningful. This is set by the drivers when reading measurements, from
+    /// the fan cleaning the driver started or observed in the sensor's status
+    /// register, until it is assumed to have completed (see
+    /// [`State::is_fan_cleaning()`](crate::State::is_fan_cleaning));
+    /// measurements decoded without a driver never have it set.
+    #[inline]
+    #[must_use]
+    pub const fn during_fan_cleaning(&self) -> bool {
+        self.fan_cleaning
+    }
+
+    #[must_use]
+    pub(crate) const fn with_fan_cleaning(mut self, fan_cleaning: bool) -> Self {
+        self.fan_cleaning = fan_cleaning;
+        self
+    }
+}
+
+#[cfg(feature = "float")]
 impl Measurements {
     /// Returns the ambient temperature in Celcius as a [`f32`], or [`None`] if
     /// no temperature reading was present.
     #[must_use]
     pub fn temp_c(&self) -> Option<f32> {
-        scale_float!(self.temp, 200.0)
+        scale_float!(self.temp, TEMP_SCALE)
     }
 
     /// Returns the ambient relative humidity percentage (%RH) as a [`f32`], or
     /// [`None`] if no humidity reading was present.
     #[must_use]
     pub fn relative_humidity(&self) -> Option<f32> {
-        scale_float!(self.rh, 100.0)
+        scale_float!(self.rh, RH_SCALE)
     }
 
     /// Returns the volatile organic componds (VOC) index as a [`f32`], or
     /// [`None`] if no VOC index reading was present.
     #[must_use]
     pub fn voc_index(&self) -> Option<f32> {
-        scale_float!(self.voc, 10.0)
+        scale_float!(self.voc, INDEX_SCALE)
     }
 
     /// Returns the nitrogen oxide (NOx) index as a [`f32`], or [`None`] if no
     /// NOx index reading was present.
     #[must_use]
     pub fn nox_index(&self) -> Option<f32> {
-        scale_float!(self.nox, 10.0)
+        scale_float!(self.nox, INDEX_SCALE)
     }
 
     /// Returns the concentration of particulate matter under 1.0 micrometers
@@ -287,12 +663,11 @@ impl Measurements {
     ///
     /// A PM<sub>1.0</sub> reading will not be present in a measurement if the
     /// sensor is not configured to measure particlulate matter concentration
-    /// (e.g. measurement was started using the
-    /// `start_measurement_no_particulates` command), or if there is an error
-    /// with the particulate matter sensor.
+    /// (see [`pm_disabled_by_mode()`](Self::pm_disabled_by_mode)), or if there
+    /// is an error with the particulate matter sensor.
     #[must_use]
     pub fn pm1_0(&self) -> Option<f32> {
-        scale_float!(self.pm1_0, 10.0)
+        scale_float!(self.pm1_0, PM_SCALE)
     }
 
     /// Returns the concentration of particulate matter under 2.5 micrometers
@@ -301,12 +676,11 @@ impl Measurements {
     ///
     /// A PM<sub>2.5</sub> reading will not be present in a measurement if the
     /// sensor is not configured to measure particlulate matter concentration
-    /// (e.g. measurement was started using the
-    /// `start_measurement_no_particulates` command), or if there is an error
-    /// with the particulate matter sensor.
+    /// (see [`pm_disabled_by_mode()`](Self::pm_disabled_by_mode)), or if there
+    /// is an error with the particulate matter sensor.
     #[must_use]
     pub fn pm2_5(&self) -> Option<f32> {
-        scale_float!(self.pm2_5, 10.0)
+        scale_float!(self.pm2_5, PM_SCALE)
     }
 
     /// Returns the concentration of particulate matter under 4.0 micrometers
@@ -315,12 +689,11 @@ impl Measurements {
     ///
     /// A PM<sub>4.5</sub> reading will not be present in a measurement if the
     /// sensor is not configured to measure particlulate matter concentration
-    /// (e.g. measurement was started using the
-    /// `start_measurement_no_particulates` command), or if there is an error
-    /// with the particulate matter sensor.
+    /// (see [`pm_disabled_by_mode()`](Self::pm_disabled_by_mode)), or if there
+    /// is an error with the particulate matter sensor.
     #[must_use]
     pub fn pm4_0(&self) -> Option<f32> {
-        scale_float!(self.pm4_0, 10.0)
+        scale_float!(self.pm4_0, PM_SCALE)
     }
 
     /// Returns the concentration of particulate matter under 4.0 micrometers
@@ -329,12 +702,285 @@ impl Measurements {
     ///
     /// A PM<sub>4.5</sub> reading will not be present in a measurement if the
     /// sensor is not configured to measure particlulate matter concentration
-    /// (e.g. measurement was started using the
-    /// `start_measurement_no_particulates` command), or if there is an error
-    /// with the particulate matter sensor.
+    /// (see [`pm_disabled_by_mode()`](Self::pm_disabled_by_mode)), or if there
+    /// is an error with the particulate matter sensor.
     #[must_use]
     pub fn pm10_0(&self) -> Option<f32> {
-        scale_float!(self.pm10_0, 10.0)
+        scale_float!(self.pm10_0, PM_SCALE)
+    }
+}
+
+impl Measurements {
+    /// Returns the ambient temperature in Celcius as a fixed-point value, or
+    /// [`None`] if no temperature reading was present.
+    #[must_use]
+    pub fn temp_c_fixed(&self) -> Option<Fixed<i16, TEMP_SCALE>> {
+        self.temp.map(Fixed::from_raw)
+    }
+
+    /// Returns the ambient relative humidity percentage (%RH) as a fixed-point
+    /// value, or [`None`] if no humidity reading was present.
+    #[must_use]
+    pub fn relative_humidity_fixed(&self) -> Option<Fixed<i16, RH_SCALE>> {
+        self.rh.map(Fixed::from_raw)
+    }
+
+    /// Returns the VOC index as a fixed-point value, or [`None`] if no VOC
+    /// index reading was present.
+    #[must_use]
+    pub fn voc_index_fixed(&self) -> Option<Fixed<i16, INDEX_SCALE>> {
+        self.voc.map(Fixed::from_raw)
+    }
+
+    /// Returns the NOx index as a fixed-point value, or [`None`] if no NOx
+    /// index reading was present.
+    #[must_use]
+    pub fn nox_index_fixed(&self) -> Option<Fixed<i16, INDEX_SCALE>> {
+        self.nox.map(Fixed::from_raw)
+    }
+
+    /// Returns the PM<sub>1.0</sub> concentration in µg/m³ as a fixed-point
+    /// value, or [`None`] if no PM<sub>1.0</sub> reading was present.
+    #[must_use]
+    pub fn pm1_0_fixed(&self) -> Option<Fixed<u16, PM_SCALE>> {
+        self.pm1_0.map(Fixed::from_raw)
+    }
+
+    /// Returns the PM<sub>2.5</sub> concentration in µg/m³ as a fixed-point
+    /// value, or [`None`] if no PM<sub>2.5</sub> reading was present.
+    #[must_use]
+    pub fn pm2_5_fixed(&self) -> Option<Fixed<u16, PM_SCALE>> {
+        self.pm2_5.map(Fixed::from_raw)
+    }
+
+    /// Returns the PM<sub>4.0</sub> concentration in µg/m³ as a fixed-point
+    /// value, or [`None`] if no PM<sub>4.0</sub> reading was present.
+    #[must_use]
+    pub fn pm4_0_fixed(&self) -> Option<Fixed<u16, PM_SCALE>> {
+        self.pm4_0.map(Fixed::from_raw)
+    }
+
+    /// Returns the PM<sub>10.0</sub> concentration in µg/m³ as a fixed-point
+    /// value, or [`None`] if no PM<sub>10.0</sub> reading was present.
+    #[must_use]
+    pub fn pm10_0_fixed(&self) -> Option<Fixed<u16, PM_SCALE>> {
+        self.pm10_0.map(Fixed::from_raw)
+    }
+}
+
+#[cfg(feature = "units")]
+impl Measurements {
+    /// Returns the ambient temperature as a [`Celsius`] value, or [`None`] if
+    /// no temperature reading was present.
+    #[must_use]
+    pub fn temperature(&self) -> Option<Celsius> {
+        self.temp_c().map(Celsius)
+    }
+
+    /// Returns the ambient relative humidity as a [`Percent`] value, or
+    /// [`None`] if no humidity reading was present.
+    #[must_use]
+    pub fn humidity(&self) -> Option<Percent> {
+        self.relative_humidity().map(Percent)
+    }
+
+    /// Returns the PM<sub>1.0</sub> concentration as a [`MassConcentration`],
+    /// or [`None`] if no PM<sub>1.0</sub> reading was present.
+    ///
+    /// See [`pm1_0()`](Self::pm1_0) for details.
+    #[must_use]
+    pub fn pm1_0_mass(&self) -> Option<MassConcentration> {
+        self.pm1_0().map(MassConcentration)
+    }
+
+    /// Returns the PM<sub>2.5</sub> concentration as a [`MassConcentration`],
+    /// or [`None`] if no PM<sub>2.5</sub> reading was present.
+    ///
+    /// See [`pm2_5()`](Self::pm2_5) for details.
+    #[must_use]
+    pub fn pm2_5_mass(&self) -> Option<MassConcentration> {
+        self.pm2_5().map(MassConcentration)
+    }
+
+    /// Returns the PM<sub>4.0</sub> concentration as a [`MassConcentration`],
+    /// or [`None`] if no PM<sub>4.0</sub> reading was present.
+    ///
+    /// See [`pm4_0()`](Self::pm4_0) for details.
+    #[must_use]
+    pub fn pm4_0_mass(&self) -> Option<MassConcentration> {
+        self.pm4_0().map(MassConcentration)
+    }
+
+    /// Returns the PM<sub>10.0</sub> concentration as a [`MassConcentration`],
+    /// or [`None`] if no PM<sub>10.0</sub> reading was present.
+    ///
+    /// See [`pm10_0()`](Self::pm10_0) for details.
+    #[must_use]
+    pub fn pm10_0_mass(&self) -> Option<MassConcentration> {
+        self.pm10_0().map(MassConcentration)
+    }
+}
+
+// === impl Channel ===
+
+impl Channel {
+    /// All channels, in the order in which they are sent on the wire.
+    pub const ALL: [Self; 8] = [
+        Self::Pm1_0,
+        Self::Pm2_5,
+        Self::Pm4_0,
+        Self::Pm10_0,
+        Self::RelativeHumidity,
+        Self::Temperature,
+        Self::VocIndex,
+        Self::NoxIndex,
+    ];
+
+    /// Returns this channel's reading from `measurements`, or [`None`] if it
+    /// is not present.
+    #[cfg(feature = "float")]
+    #[must_use]
+    pub fn value(self, measurements: &Measurements) -> Option<f32> {
+        match self {
+            Self::Pm1_0 => measurements.pm1_0(),
+            Self::Pm2_5 => measurements.pm2_5(),
+            Self::Pm4_0 => measurements.pm4_0(),
+            Self::Pm10_0 => measurements.pm10_0(),
+            Self::RelativeHumidity => measurements.relative_humidity(),
+            Self::Temperature => measurements.temp_c(),
+            Self::VocIndex => measurements.voc_index(),
+            Self::NoxIndex => measurements.nox_index(),
+        }
+    }
+
+    /// Returns this channel's raw reading from `measurements`, in the units
+    /// sent on the wire, or [`None`] if it is not present.
+    ///
+    /// See the [`Measurements`] documentation for the scale factor of each
+    /// channel.
+    #[must_use]
+    pub fn raw_value(self, measurements: &Measurements) -> Option<i32> {
+        let word = measurements.to_raw_words()[self.index()];
+        match self {
+            Self::Pm1_0 | Self::Pm2_5 | Self::Pm4_0 | Self::Pm10_0 => {
+                present_u16(word).map(i32::from)
+            }
+            _ => present_i16(word as i16).map(i32::from),
+        }
+    }
+
+    pub(crate) const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// === impl MeasurementsBuilder ===
+
+macro_rules! builder_fixed_setters {
+    ($($(#[$meta:meta])* $name:ident => $field:ident: Fixed<$T:ty, $scale:ident>;)+) => {
+        $(
+            $(#[$meta])*
+            pub const fn $name(mut self, value: Fixed<$T, $scale>) -> Self {
+                self.measurements.$field = Some(value.raw());
+                self
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "float")]
+macro_rules! builder_float_setters {
+    ($($(#[$meta:meta])* $name:ident => $field:ident: $T:ty, $scale:ident;)+) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(mut self, value: f32) -> Self {
+                self.measurements.$field = Some(unscale_float!(value, $scale, $T));
+                self
+            }
+        )+
+    };
+}
+
+impl MeasurementsBuilder {
+    builder_fixed_setters! {
+        /// Sets the PM<sub>1.0</sub> concentration from a fixed-point value.
+        pm1_0_fixed => pm1_0: Fixed<u16, PM_SCALE>;
+        /// Sets the PM<sub>2.5</sub> concentration from a fixed-point value.
+        pm2_5_fixed => pm2_5: Fixed<u16, PM_SCALE>;
+        /// Sets the PM<sub>4.0</sub> concentration from a fixed-point value.
+        pm4_0_fixed => pm4_0: Fixed<u16, PM_SCALE>;
+        /// Sets the PM<sub>10.0</sub> concentration from a fixed-point value.
+        pm10_0_fixed => pm10_0: Fixed<u16, PM_SCALE>;
+        /// Sets the relative humidity from a fixed-point value.
+        relative_humidity_fixed => rh: Fixed<i16, RH_SCALE>;
+        /// Sets the temperature from a fixed-point value.
+        temp_c_fixed => temp: Fixed<i16, TEMP_SCALE>;
+        /// Sets the VOC index from a fixed-point value.
+        voc_index_fixed => voc: Fixed<i16, INDEX_SCALE>;
+        /// Sets the NOx index from a fixed-point value.
+        nox_index_fixed => nox: Fixed<i16, INDEX_SCALE>;
+    }
+
+    /// Sets whether the particulate matter readings are absent because the
+    /// sensor was measuring with particulate matter disabled.
+    ///
+    /// See [`Measurements::pm_disabled_by_mode()`].
+    pub const fn pm_disabled_by_mode(mut self, disabled: bool) -> Self {
+        self.measurements.pm_disabled = disabled;
+        self
+    }
+
+    /// Sets the [`ChannelStability`] of the measurements.
+    ///
+    /// See [`Measurements::stability()`].
+    pub const fn stability(mut self, stability: ChannelStability) -> Self {
+        self.measurements.stability = stability;
+        self
+    }
+
+    /// Sets whether the measurements were taken during fan cleaning.
+    ///
+    /// See [`Measurements::during_fan_cleaning()`].
+    pub const fn during_fan_cleaning(mut self, fan_cleaning: bool) -> Self {
+        self.measurements.fan_cleaning = fan_cleaning;
+        self
+    }
+
+    /// Returns the constructed [`Measurements`].
+    ///
+    /// Any fixed-point value set to the "not present" value (`0xFFFF` for
+    /// unsigned values, `0x7FFF` for signed values) will not be present.
+    #[must_use]
+    pub const fn build(self) -> Measurements {
+        let mut measurements = Measurements::from_raw_words(self.measurements.to_raw_words());
+        measurements.pm_disabled = self.measurements.pm_disabled;
+        measurements.stability = self.measurements.stability;
+        measurements.fan_cleaning = self.measurements.fan_cleaning;
+        measurements
+    }
+}
+
+/// Floating-point values are rounded to the nearest value representable on the
+/// wire, and saturated to the wire format's range.
+#[cfg(feature = "float")]
+impl MeasurementsBuilder {
+    builder_float_setters! {
+        /// Sets the PM<sub>1.0</sub> concentration in µg/m³.
+        pm1_0 => pm1_0: u16, PM_SCALE;
+        /// Sets the PM<sub>2.5</sub> concentration in µg/m³.
+        pm2_5 => pm2_5: u16, PM_SCALE;
+        /// Sets the PM<sub>4.0</sub> concentration in µg/m³.
+        pm4_0 => pm4_0: u16, PM_SCALE;
+        /// Sets the PM<sub>10.0</sub> concentration in µg/m³.
+        pm10_0 => pm10_0: u16, PM_SCALE;
+        /// Sets the relative humidity percentage (%RH).
+        relative_humidity => rh: i16, RH_SCALE;
+        /// Sets the temperature in Celcius.
+        temp_c => temp: i16, TEMP_SCALE;
+        /// Sets the VOC index.
+        voc_index => voc: i16, INDEX_SCALE;
+        /// Sets the NOx index.
+        nox_index => nox: i16, INDEX_SCALE;
     }
 }
 
@@ -352,19 +998,50 @@ impl Decode for RawSignals {
     }
 }
 
+impl Encode for RawSignals {
+    fn encode(&self, buf: &mut Self::Buf) {
+        let Self {
+            humidity,
+            temp,
+            voc,
+            nox,
+        } = *self;
+        put_word(buf, 0, humidity.unwrap_or(i16::MAX) as u16);
+        put_word(buf, 3, temp.unwrap_or(i16::MAX) as u16);
+        put_word(buf, 6, voc.unwrap_or(u16::MAX));
+        put_word(buf, 9, nox.unwrap_or(u16::MAX));
+    }
+}
+
 impl RawSignals {
     /// Returns the raw temperature reading in Celcius as a [`f32`], or [`None`] if
     /// no temperature reading was present.
+    #[cfg(feature = "float")]
     #[must_use]
     pub fn raw_temp_c(&self) -> Option<f32> {
-        scale_float!(self.temp, 200.0)
+        scale_float!(self.temp, TEMP_SCALE)
     }
 
     /// Returns the raw relative humidity percentage (%RH) as a [`f32`], or
     /// [`None`] if no humidity reading was present.
+    #[cfg(feature = "float")]
     #[must_use]
     pub fn raw_relative_humidity(&self) -> Option<f32> {
-        scale_float!(self.humidity, 100.0)
+        scale_float!(self.humidity, RH_SCALE)
+    }
+
+    /// Returns the raw temperature reading in Celcius as a fixed-point value,
+    /// or [`None`] if no temperature reading was present.
+    #[must_use]
+    pub fn raw_temp_c_fixed(&self) -> Option<Fixed<i16, TEMP_SCALE>> {
+        self.temp.map(Fixed::from_raw)
+    }
+
+    /// Returns the raw relative humidity percentage (%RH) as a fixed-point
+    /// value, or [`None`] if no humidity reading was present.
+    #[must_use]
+    pub fn raw_relative_humidity_fixed(&self) -> Option<Fixed<i16, RH_SCALE>> {
+        self.humidity.map(Fixed::from_raw)
     }
 
     /// Returns the raw VOC signal as a [`u16`], or [`None`] if no VOC signal
@@ -387,9 +1064,53 @@ impl RawSignals {
     /// - The NOx signal will not be present for the first 10-11 seconds after
     ///   the sensor is powered up.
     #[must_use]
-    pub fn nox_index(&self) -> Option<u16> {
+    pub fn raw_nox_signal(&self) -> Option<u16> {
         self.nox
     }
+
+    /// Returns the raw nitrogen oxide (NOx) signal as a [`u16`], or [`None`] if
+    /// no NOx signal was present.
+    ///
+    /// Despite its name, this method returns the raw NOx signal, *not* the NOx
+    /// index.
+    #[must_use]
+    #[deprecated(note = "this returns the raw NOx signal; use `raw_nox_signal()` instead")]
+    pub fn nox_index(&self) -> Option<u16> {
+        self.raw_nox_signal()
+    }
+
+    /// Returns the raw temperature reading as a [`RawCelsius`] value, or
+    /// [`None`] if no temperature reading was present.
+    #[cfg(feature = "float")]
+    #[must_use]
+    pub fn raw_temp(&self) -> Option<RawCelsius> {
+        self.raw_temp_c().map(RawCelsius)
+    }
+
+    /// Returns the raw relative humidity reading as a [`RawPercent`] value, or
+    /// [`None`] if no humidity reading was present.
+    #[cfg(feature = "float")]
+    #[must_use]
+    pub fn raw_humidity(&self) -> Option<RawPercent> {
+        self.raw_relative_humidity().map(RawPercent)
+    }
+
+    /// Returns the raw VOC signal as [`VocTicks`], or [`None`] if no VOC signal
+    /// was present.
+    #[must_use]
+    pub fn voc_ticks(&self) -> Option<VocTicks> {
+        self.voc.map(VocTicks)
+    }
+
+    /// Returns the raw NOx signal as [`NoxTicks`], or [`None`] if no NOx signal
+    /// was present.
+    ///
+    /// The NOx signal will not be present for the first 10-11 seconds after the
+    /// sensor is powered up.
+    #[must_use]
+    pub fn nox_ticks(&self) -> Option<NoxTicks> {
+        self.nox.map(NoxTicks)
+    }
 }
 
 // === impl RawString ===
@@ -397,6 +1118,25 @@ impl RawSignals {
 impl RawString {
     const LEN: usize = 32;
 
+    /// Returns a new `RawString` containing `s`, or [`None`] if `s` is longer
+    /// than 32 bytes or contains non-ASCII or NUL characters.
+    #[must_use]
+    pub fn new(s: &str) -> Option<Self> {
+        if s.len() > Self::LEN {
+            return None;
+        }
+        let mut this = Self {
+            bytes: [0; Self::LEN],
+            len: 0,
+        };
+        for c in s.bytes() {
+            if this.push_char(c).ok()? {
+                return None;
+            }
+        }
+        Some(this)
+    }
+
     #[must_use]
     pub fn as_str(&self) -> &str {
         core::str::from_utf8(&self.bytes[..self.len]).unwrap_or("<invalid utf-8>")
@@ -416,17 +1156,15 @@ impl RawString {
 }
 
 impl Decode for RawString {
-    type Buf = [u8; 47];
+    type Buf = [u8; 48];
     fn decode(buf: &Self::Buf) -> Result<Self, DecodeError> {
         let mut this = Self {
             bytes: [0; Self::LEN],
             len: 0,
         };
+        // Validate every word, including the padding after the terminator.
+        crc8::validate(&buf[..])?;
         for chunk in buf.chunks(3) {
-            if crc8::calculate(&chunk[..2]) != chunk[2] {
-                return Err(DecodeError::Crc);
-            }
-
             if this.push_char(chunk[0])? {
                 break;
             }
@@ -439,6 +1177,22 @@ impl Decode for RawString {
     }
 }
 
+impl Encode for RawString {
+    fn encode(&self, buf: &mut Self::Buf) {
+        // Unused bytes are zeroed, which also NUL-terminates the string.
+        for (i, pair) in self.bytes.chunks(2).enumerate() {
+            put_word(buf, i * 3, u16::from_be_bytes([pair[0], pair[1]]));
+        }
+    }
+}
+
+#[cfg(feature = "fmt")]
+impl core::fmt::Debug for RawString {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
 // === impl VersionInfo ===
 
 impl Decode for VersionInfo {
@@ -451,19 +1205,38 @@ impl Decode for VersionInfo {
                 major: buf[0],
                 minor: buf[1],
             },
-            firmware_debug: buf[4] != 0,
+            firmware_debug: buf[3] != 0,
             hardware: Version {
-                major: buf[5],
-                minor: buf[7],
+                major: buf[4],
+                minor: buf[6],
             },
             protocol: Version {
-                major: buf[8],
-                minor: buf[10],
+                major: buf[7],
+                minor: buf[9],
             },
         })
     }
 }
 
+impl Encode for VersionInfo {
+    fn encode(&self, buf: &mut Self::Buf) {
+        let Self {
+            firmware,
+            firmware_debug,
+            hardware,
+            protocol,
+        } = self;
+        put_word(buf, 0, u16::from_be_bytes([firmware.major, firmware.minor]));
+        put_word(
+            buf,
+            3,
+            u16::from_be_bytes([u8::from(*firmware_debug), hardware.major]),
+        );
+        put_word(buf, 6, u16::from_be_bytes([hardware.minor, protocol.major]));
+        put_word(buf, 9, u16::from_be_bytes([protocol.minor, 0]));
+    }
+}
+
 impl VersionInfo {
     /// Returns `true` if the sensor firmware supports the `read_pm_values`
     /// command.
@@ -482,6 +1255,146 @@ impl core::fmt::Debug for Version {
     }
 }
 
+// === impl SensorStatus ===
+
+impl Decode for SensorStatus {
+    type Buf = [u8; 6];
+    fn decode(buf: &Self::Buf) -> Result<Self, DecodeError> {
+        crc8::validate(&buf[..])?;
+        let bits = u32::from_be_bytes([buf[0], buf[1], buf[3], buf[4]]);
+        Ok(Self::from_bits_retain(bits))
+    }
+}
+
+impl Encode for SensorStatus {
+    fn encode(&self, buf: &mut Self::Buf) {
+        let bits = self.bits();
+        put_word(buf, 0, (bits >> 16) as u16);
+        put_word(buf, 3, bits as u16);
+    }
+}
+
+// === impl TemperatureOffset ===
+
+impl Decode for TemperatureOffset {
+    type Buf = [u8; 9];
+    fn decode(buf: &Self::Buf) -> Result<Self, DecodeError> {
+        crc8::validate(&buf[..])?;
+        Ok(Self {
+            offset: Fixed::from_raw(i16::from_be_bytes([buf[0], buf[1]])),
+            slope: Fixed::from_raw(i16::from_be_bytes([buf[3], buf[4]])),
+            time_constant_s: u16::from_be_bytes([buf[6], buf[7]]),
+        })
+    }
+}
+
+impl Encode for TemperatureOffset {
+    fn encode(&self, buf: &mut Self::Buf) {
+        put_word(buf, 0, self.offset.raw() as u16);
+        put_word(buf, 3, self.slope.raw() as u16);
+        put_word(buf, 6, self.time_constant_s);
+    }
+}
+
+impl TemperatureOffset {
+    /// No temperature offset, the sensor's default.
+    pub const NONE: Self = Self {
+        offset: Fixed::from_raw(0),
+        slope: Fixed::from_raw(0),
+        time_constant_s: 0,
+    };
+}
+
+impl Default for TemperatureOffset {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+// === impl AlgorithmTuning ===
+
+impl Decode for AlgorithmTuning {
+    type Buf = [u8; 18];
+    fn decode(buf: &Self::Buf) -> Result<Self, DecodeError> {
+        crc8::validate(&buf[..])?;
+        let word = |idx: usize| i16::from_be_bytes([buf[idx], buf[idx + 1]]);
+        Ok(Self {
+            index_offset: word(0),
+            learning_time_offset_hours: word(3),
+            learning_time_gain_hours: word(6),
+            gating_max_duration_minutes: word(9),
+            std_initial: word(12),
+            gain_factor: word(15),
+        })
+    }
+}
+
+impl Encode for AlgorithmTuning {
+    fn encode(&self, buf: &mut Self::Buf) {
+        let words = [
+            self.index_offset,
+            self.learning_time_offset_hours,
+            self.learning_time_gain_hours,
+            self.gating_max_duration_minutes,
+            self.std_initial,
+            self.gain_factor,
+        ];
+        for (i, word) in words.into_iter().enumerate() {
+            put_word(buf, i * 3, word as u16);
+        }
+    }
+}
+
+impl AlgorithmTuning {
+    /// The default tuning parameters for the VOC index.
+    pub const VOC_DEFAULT: Self = Self {
+        index_offset: 100,
+        learning_time_offset_hours: 12,
+        learning_time_gain_hours: 12,
+        gating_max_duration_minutes: 180,
+        std_initial: 50,
+        gain_factor: 230,
+    };
+
+    /// The default tuning parameters for the NOx index.
+    pub const NOX_DEFAULT: Self = Self {
+        index_offset: 1,
+        learning_time_offset_hours: 12,
+        learning_time_gain_hours: 12,
+        gating_max_duration_minutes: 720,
+        std_initial: 50,
+        gain_factor: 230,
+    };
+}
+
+// === impl RhtAccelerationMode ===
+
+impl RhtAccelerationMode {
+    pub(crate) fn from_raw(raw: u16) -> Result<Self, DecodeError> {
+        match raw {
+            0 => Ok(Self::Low),
+            1 => Ok(Self::High),
+            2 => Ok(Self::Medium),
+            _ => Err(DecodeError::msg(
+                "RH/T acceleration mode must be 0, 1, or 2",
+            )),
+        }
+    }
+}
+
+impl Decode for RhtAccelerationMode {
+    type Buf = [u8; 3];
+    fn decode(buf: &Self::Buf) -> Result<Self, DecodeError> {
+        Self::from_raw(u16::decode(buf)?)
+    }
+}
+
+impl Encode for RhtAccelerationMode {
+    fn encode(&self, buf: &mut Self::Buf) {
+        (*self as u16).encode(buf);
+    }
+}
+
 // === impl u16 ===
 
 impl Decode for u16 {
@@ -493,9 +1406,282 @@ impl Decode for u16 {
 }
 
 impl Encode for u16 {
-    fn encode(self, buf: &mut [u8]) {
-        let bytes = self.to_be_bytes();
-        buf[0..2].copy_from_slice(&bytes);
-        buf[2] = crc8::calculate(&bytes);
+    fn encode(&self, buf: &mut Self::Buf) {
+        put_word(buf, 0, *self);
+    }
+}
+
+// === impl u32 ===
+
+impl Decode for u32 {
+    type Buf = [u8; 6];
+    fn decode(buf: &Self::Buf) -> Result<Self, DecodeError> {
+        crc8::validate(&buf[..])?;
+        Ok(Self::from_be_bytes([buf[0], buf[1], buf[3], buf[4]]))
+    }
+}
+
+impl Encode for u32 {
+    fn encode(&self, buf: &mut Self::Buf) {
+        put_word(buf, 0, (*self >> 16) as u16);
+        put_word(buf, 3, *self as u16);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that `wire` decodes to `expected`, that `expected` encodes to
+    /// `wire`, and that corrupting the last checksum is detected.
+    fn check<T>(wire: T::Buf, expected: &T)
+    where
+        T: Encode + PartialEq,
+        T::Buf: Copy + AsRef<[u8]> + AsMut<[u8]>,
+    {
+        assert!(T::decode(&wire).ok().as_ref() == Some(expected));
+
+        let mut buf = wire;
+        buf.as_mut().fill(0xAA);
+        expected.encode(&mut buf);
+        assert_eq!(buf.as_ref(), wire.as_ref());
+
+        let mut corrupt = wire;
+        let len = corrupt.as_ref().len();
+        corrupt.as_mut()[len - 1] ^= 0x01;
+        assert!(matches!(T::decode(&corrupt), Err(DecodeError::Crc)));
+    }
+
+    #[test]
+    fn raw_string_datasheet_example() {
+        // 32 bytes of string data are sent as 16 words, each followed by its
+        // CRC, for 48 bytes in total.
+        let mut buf = [0; 48];
+        buf[..9].copy_from_slice(&[b'S', b'E', 0x83, b'N', b'5', 0x55, b'5', 0x00, 0x44]);
+        for word in buf[9..].chunks_mut(3) {
+            word.copy_from_slice(&[0x00, 0x00, 0x81]);
+        }
+        let name = RawString::decode(&buf).ok().unwrap();
+        assert_eq!(name.as_str(), "SEN55");
+    }
+
+    #[test]
+    fn version_info_datasheet_example() {
+        #[rustfmt::skip]
+        let buf = [
+            0x02, 0x00, 0x58, // firmware major, firmware minor
+            0x00, 0x04, 0x45, // firmware debug, hardware major
+            0x00, 0x01, 0xB0, // hardware minor, protocol major
+            0x00, 0x00, 0x81, // protocol minor, padding
+        ];
+        let version = VersionInfo::decode(&buf).ok().unwrap();
+        assert!(version.firmware == Version { major: 2, minor: 0 });
+        assert!(!version.firmware_debug);
+        assert!(version.hardware == Version { major: 4, minor: 0 });
+        assert!(version.protocol == Version { major: 1, minor: 0 });
+    }
+
+    #[test]
+    fn crc8_datasheet_example() {
+        check([0xBE, 0xEF, 0x92], &0xBEEF_u16);
+    }
+
+    #[test]
+    fn data_ready() {
+        check([0x00, 0x00, 0x81], &DataReady(false));
+        check([0x00, 0x01, 0xB0], &DataReady(true));
+        assert!(DataReady::decode(&[0x00, 0x02, 0xE3]).is_err());
+    }
+
+    #[test]
+    fn measurements() {
+        #[rustfmt::skip]
+        let wire = [
+            0x00, 0x17, 0x55, // PM1.0: 2.3 µg/m³
+            0x00, 0x29, 0x8F, // PM2.5: 4.1 µg/m³
+            0x00, 0x34, 0x80, // PM4.0: 5.2 µg/m³
+            0x00, 0x39, 0xCC, // PM10.0: 5.7 µg/m³
+            0x11, 0xA0, 0xE7, // RH: 45.12 %
+            0x12, 0x5C, 0x35, // T: 23.5 °C
+            0x03, 0xE8, 0xD4, // VOC index: 100
+            0x00, 0x0A, 0x5A, // NOx index: 1
+        ];
+        let expected = Measurements::from_raw_words([23, 41, 52, 57, 4512, 4700, 1000, 10]);
+        check(wire, &expected);
+        assert_eq!(expected.pm2_5_fixed().map(Fixed::raw), Some(41));
+        assert_eq!(expected.temp_c_fixed().map(Fixed::raw), Some(4700));
+
+        #[rustfmt::skip]
+        let not_present = [
+            0xFF, 0xFF, 0xAC,
+            0xFF, 0xFF, 0xAC,
+            0xFF, 0xFF, 0xAC,
+            0xFF, 0xFF, 0xAC,
+            0x7F, 0xFF, 0x8F,
+            0x7F, 0xFF, 0x8F,
+            0x7F, 0xFF, 0x8F,
+            0x7F, 0xFF, 0x8F,
+        ];
+        check(not_present, &Measurements::builder().build());
+    }
+
+    #[test]
+    fn measurements_annotations_are_not_compared() {
+        let annotated = Measurements::from_raw_words([23, 41, 52, 57, 4512, 4700, 1000, 10])
+            .with_particulate_mode(ParticulateMode::Disabled)
+            .with_stability(ChannelStability::after(0, ParticulateMode::Disabled))
+            .with_fan_cleaning(true);
+        let mut buf = [0; 24];
+        annotated.encode(&mut buf);
+        let decoded = Measurements::decode(&buf).ok().unwrap();
+        assert!(decoded == annotated);
+        assert!(!decoded.pm_disabled_by_mode());
+        assert!(decoded.stability() == ChannelStability::STABLE);
+        assert!(!decoded.during_fan_cleaning());
+
+        let other = Measurements::from_raw_words([23, 41, 52, 57, 4512, 4700, 1000, 20]);
+        assert!(other != annotated);
+    }
+
+    #[test]
+    fn raw_signals() {
+        #[rustfmt::skip]
+        let wire = [
+            0x11, 0xA0, 0xE7, // RH: 45.12 %
+            0x12, 0x5C, 0x35, // T: 23.5 °C
+            0x75, 0x30, 0x08, // VOC: 30000 ticks
+            0x3E, 0x80, 0x24, // NOx: 16000 ticks
+        ];
+        let expected = RawSignals {
+            humidity: Some(4512),
+            temp: Some(4700),
+            voc: Some(30000),
+            nox: Some(16000),
+        };
+        check(wire, &expected);
+
+        #[rustfmt::skip]
+        let not_present = [
+            0x7F, 0xFF, 0x8F,
+            0x7F, 0xFF, 0x8F,
+            0xFF, 0xFF, 0xAC,
+            0xFF, 0xFF, 0xAC,
+        ];
+        let expected = RawSignals {
+            humidity: None,
+            temp: None,
+            voc: None,
+            nox: None,
+        };
+        check(not_present, &expected);
+    }
+
+    #[test]
+    fn raw_string() {
+        // The product name of a SEN55, NUL-terminated and zero-padded to 32
+        // bytes.
+        let mut wire = [0; 48];
+        wire[..9].copy_from_slice(&[b'S', b'E', 0x83, b'N', b'5', 0x55, b'5', 0x00, 0x44]);
+        for word in wire[9..].chunks_mut(3) {
+            word.copy_from_slice(&[0x00, 0x00, 0x81]);
+        }
+        let expected = RawString::new("SEN55").unwrap();
+        check(wire, &expected);
+        assert_eq!(expected.as_str(), "SEN55");
+
+        // A string using all 32 bytes has no NUL terminator.
+        let full = RawString::new("0123456789ABCDEF0123456789ABCDEF").unwrap();
+        let mut buf = [0; 48];
+        full.encode(&mut buf);
+        assert!(RawString::decode(&buf).ok() == Some(full));
+
+        assert!(RawString::new("0123456789ABCDEF0123456789ABCDEF0").is_none());
+    }
+
+    #[test]
+    fn version_info() {
+        #[rustfmt::skip]
+        let wire = [
+            0x02, 0x00, 0x58, // firmware major, firmware minor
+            0x00, 0x04, 0x45, // firmware debug, hardware major
+            0x00, 0x01, 0xB0, // hardware minor, protocol major
+            0x00, 0x00, 0x81, // protocol minor, padding
+        ];
+        let expected = VersionInfo {
+            firmware: Version { major: 2, minor: 0 },
+            firmware_debug: false,
+            hardware: Version { major: 4, minor: 0 },
+            protocol: Version { major: 1, minor: 0 },
+        };
+        check(wire, &expected);
+    }
+
+    #[test]
+    fn sensor_status() {
+        let wire = [0x00, 0x08, 0x38, 0x00, 0x10, 0xC2];
+        check(
+            wire,
+            &(SensorStatus::FAN_CLEANING | SensorStatus::FAN_ERROR),
+        );
+    }
+
+    #[test]
+    fn temperature_offset() {
+        #[rustfmt::skip]
+        let wire = [
+            0x01, 0x2C, 0x8E, // offset: 1.5 °C
+            0x00, 0x64, 0xFE, // slope: 0.01
+            0x00, 0x0A, 0x5A, // time constant: 10 s
+        ];
+        let expected = TemperatureOffset {
+            offset: Fixed::from_raw(300),
+            slope: Fixed::from_raw(100),
+            time_constant_s: 10,
+        };
+        check(wire, &expected);
+
+        // Signed words are two's complement.
+        #[rustfmt::skip]
+        let wire = [
+            0xFF, 0x38, 0x7C, // offset: -1.0 °C
+            0x00, 0x00, 0x81,
+            0x00, 0x00, 0x81,
+        ];
+        let expected = TemperatureOffset {
+            offset: Fixed::from_raw(-200),
+            ..TemperatureOffset::NONE
+        };
+        check(wire, &expected);
+    }
+
+    #[test]
+    fn algorithm_tuning() {
+        #[rustfmt::skip]
+        let wire = [
+            0x00, 0x64, 0xFE, // index offset: 100
+            0x00, 0x0C, 0xFC, // learning time offset: 12 h
+            0x00, 0x0C, 0xFC, // learning time gain: 12 h
+            0x00, 0xB4, 0xFA, // gating max duration: 180 min
+            0x00, 0x32, 0x26, // initial standard deviation: 50
+            0x00, 0xE6, 0xE6, // gain factor: 230
+        ];
+        check(wire, &AlgorithmTuning::VOC_DEFAULT);
+    }
+
+    #[test]
+    fn rht_acceleration_mode() {
+        check([0x00, 0x00, 0x81], &RhtAccelerationMode::Low);
+        check([0x00, 0x01, 0xB0], &RhtAccelerationMode::High);
+        check([0x00, 0x02, 0xE3], &RhtAccelerationMode::Medium);
+        assert!(matches!(
+            RhtAccelerationMode::decode(&[0x00, 0x03, 0xD2]),
+            Err(DecodeError::Msg(_))
+        ));
+    }
+
+    #[test]
+    fn u32_words() {
+        // The default auto-cleaning interval: 604800 s (one week).
+        check([0x00, 0x09, 0x09, 0x3A, 0x80, 0xA7], &604_800_u32);
     }
 }
@@ -0,0 +1,84 @@
+use embedded_hal::i2c::{ErrorType, Operation};
+
+/// The default I²C address of a TCA9548A or PCA9548 multiplexer, with all
+/// address pins low.
+pub(crate) const DEFAULT_MUX_ADDR: u8 = 0x70;
+
+/// The number of channels on a TCA9548A or PCA9548 multiplexer.
+pub(crate) const MUX_CHANNELS: usize = 8;
+
+/// An I²C bus behind a single channel of a TCA9548A or PCA9548 I²C
+/// multiplexer.
+///
+/// Before each transaction, the channel is selected by writing its bit to the
+/// multiplexer's control register, deselecting all other channels. This is
+/// returned by [`Sen5xArray::sensor()`](crate::Sen5xArray::sensor), and may
+/// also be used with other devices behind the multiplexer.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct MuxChannel<'a, I> {
+    i2c: &'a mut I,
+    mux_addr: u8,
+    channel: u8,
+}
+
+// === impl MuxChannel ===
+
+impl<'a, I> MuxChannel<'a, I> {
+    /// Returns a new `MuxChannel` for `channel` of the multiplexer at
+    /// `mux_addr` on the provided I²C bus.
+    ///
+    /// # Panics
+    ///
+    /// If `channel` is not less than 8.
+    #[must_use]
+    pub fn new(i2c: &'a mut I, mux_addr: u8, channel: u8) -> Self {
+        assert!(
+            (channel as usize) < MUX_CHANNELS,
+            "a TCA9548A has only 8 channels"
+        );
+        Self {
+            i2c,
+            mux_addr,
+            channel,
+        }
+    }
+
+    /// Returns the multiplexer channel selected by this bus.
+    #[inline]
+    #[must_use]
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    #[inline]
+    fn select(&self) -> [u8; 1] {
+        [1 << self.channel]
+    }
+}
+
+impl<I: ErrorType> ErrorType for MuxChannel<'_, I> {
+    type Error = I::Error;
+}
+
+impl<I: embedded_hal::i2c::I2c> embedded_hal::i2c::I2c for MuxChannel<'_, I> {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.i2c.write(self.mux_addr, &self.select())?;
+        self.i2c.transaction(address, operations)
+    }
+}
+
+#[cfg(feature = "embedded-hal-async")]
+impl<I: embedded_hal_async::i2c::I2c> embedded_hal_async::i2c::I2c for MuxChannel<'_, I> {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.i2c.write(self.mux_addr, &self.select()).await?;
+        self.i2c.transaction(address, operations).await
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{
+    cmd::{self, ReadCommand},
+    msg::{self, Decode},
+    Clock, CrcErrors, DecodeError, Instant, State,
+};
+use core::marker::PhantomData;
+
+/// A command which has been sent to the sensor, but whose execution time has
+/// not necessarily elapsed.
+///
+/// This is returned by the drivers' `begin_*()` methods, which write a command
+/// to the sensor without waiting for it to execute. These methods are only
+/// available on a driver with a [`Clock`], which is used to track when the
+/// command will be ready. This allows cooperative
+/// schedulers and single-threaded main loops to perform other work while the
+/// sensor executes the command, rather than blocking in a delay.
+///
+/// Once the command is [ready](Self::is_ready), the response of a read command
+/// is read by passing a reference to the `PendingCommand` to the driver's
+/// `finish_command()` method. For write commands (`PendingCommand<()>`), there is no response to
+/// read, but the sensor will not accept another command until the command is
+/// ready.
+///
+/// # Notes
+///
+/// - `finish_command()` does not wait for the command to be ready. If it is
+///   called early, it returns [`Error::Busy`](crate::Error::Busy) without
+///   reading from the sensor, and may be called again later.
+/// - The `begin_*()` methods return [`Error::Busy`](crate::Error::Busy)
+///   rather than waiting if the sensor is still executing a previous command,
+///   including one sent by a method which deferred waiting for it to the
+///   driver's clock.
+/// - Commands are not retried according to the driver's
+///   [`RetryPolicy`](crate::RetryPolicy). If finishing a command fails, it must
+///   be started again.
+#[must_use = "a pending read command must be finished to read its response"]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct PendingCommand<T> {
+    ready_at: Instant,
+    _output: PhantomData<fn() -> T>,
+}
+
+/// The response of a command which may be read using a [`PendingCommand`].
+///
+/// This trait is sealed, and implemented for the responses of the commands
+/// with `begin_*()` methods.
+pub trait CommandOutput: sealed::Sealed {}
+
+pub(crate) mod sealed {
+    use crate::{CrcErrors, DecodeError, Instant, State};
+
+    pub trait Sealed: Sized {
+        type Buf: AsMut<[u8]>;
+        const BUF: Self::Buf;
+
+        /// Returns the counter of CRC errors for this command's response.
+        fn crc_errors(errors: &mut CrcErrors) -> &mut u32;
+
+        /// Decodes the command's response, which was read at `now`, recording
+        /// it in the sensor's state.
+        fn finish(buf: &Self::Buf, state: &mut State, now: Instant) -> Result<Self, DecodeError>;
+    }
+}
+
+// === impl PendingCommand ===
+
+impl<T> PendingCommand<T> {
+    /// Returns a `PendingCommand` for a command sent at `now`, which takes
+    /// `execution_ms` milliseconds to execute.
+    pub(crate) fn new(now: Instant, execution_ms: usize) -> Self {
+        Self {
+            ready_at: now.add_millis(execution_ms as u64),
+            _output: PhantomData,
+        }
+    }
+
+    /// Returns the time at which the sensor will have finished executing the
+    /// command.
+    #[inline]
+    #[must_use]
+    pub const fn ready_at(&self) -> Instant {
+        self.ready_at
+    }
+
+    /// Returns `true` if the sensor has finished executing the command,
+    /// according to the provided [`Clock`].
+    ///
+    /// The clock must be the clock of the driver whose `begin_*()` method
+    /// returned this `PendingCommand`.
+    #[must_use]
+    pub fn is_ready(&self, clock: &impl Clock) -> bool {
+        clock.now() >= self.ready_at
+    }
+}
+
+// === impl CommandOutput ===
+
+macro_rules! impl_command_output {
+    ($($output:ty => $cmd:ty, [$bytes:literal], |$rsp:ident, $state:ident, $now:ident| $finish:expr;)+) => {
+        $(
+            impl CommandOutput for $output {}
+
+            impl sealed::Sealed for $output {
+                type Buf = [u8; $bytes];
+                const BUF: Self::Buf = [0; $bytes];
+
+                fn crc_errors(errors: &mut CrcErrors) -> &mut u32 {
+                    <$cmd as ReadCommand>::crc_errors(errors)
+                }
+
+                fn finish(
+                    buf: &Self::Buf,
+                    $state: &mut State,
+                    $now: Instant,
+                ) -> Result<Self, DecodeError> {
+                    let $rsp = <$cmd as ReadCommand>::Rsp::decode(buf)?;
+                    Ok($finish)
+                }
+            }
+        )+
+    };
+}
+
+impl_command_output! {
+    bool => cmd::ReadDataReady, [3], |rsp, state, _now| {
+        state.data_ready(rsp.0);
+        rsp.0
+    };
+    msg::Measurements => cmd::ReadMeasurement, [24], |rsp, state, now| {
+        state.measurements_read(rsp, Some(now))
+    };
+    msg::RawSignals => cmd::ReadRawSignals, [12], |rsp, _state, _now| rsp;
+    msg::SensorStatus => cmd::ReadDeviceStatus, [6], |rsp, state, now| {
+        state.status_read(rsp, Some(now));
+        rsp
+    };
+}
@@ -0,0 +1,13 @@
+/// The time after power is applied before the sensor accepts I²C commands.
+pub(crate) const POWER_UP_MS: u32 = 50;
+
+/// The number of times the sensor is probed after powering up before giving
+/// up.
+pub(crate) const PROBE_ATTEMPTS: u8 = 5;
+
+/// The time between attempts to probe the sensor after powering up.
+pub(crate) const PROBE_INTERVAL_MS: u32 = 10;
+
+/// The time for which power is removed when power-cycling the sensor, to
+/// ensure that it fully discharges.
+pub(crate) const POWER_OFF_MS: u32 = 1000;
@@ -0,0 +1,152 @@
+/// A policy for automatically retrying read commands which fail with
+/// [retriable errors](crate::Error::is_retriable).
+///
+/// By default, drivers use [`RetryPolicy::NONE`], which never retries.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct RetryPolicy {
+    max_attempts: u8,
+    backoff_ms: u32,
+    backoff_multiplier: u32,
+}
+
+/// Counters tracking how many retries were performed by a driver's
+/// [`RetryPolicy`].
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[non_exhaustive]
+pub struct RetryStats {
+    /// The total number of retries performed.
+    pub retries: u32,
+    /// The number of commands which failed at least once, but succeeded after
+    /// being retried.
+    pub recovered: u32,
+    /// The number of commands which failed with a retriable error on every
+    /// attempt permitted by the retry policy.
+    pub exhausted: u32,
+}
+
+// === impl RetryPolicy ===
+
+impl RetryPolicy {
+    /// A retry policy which never retries.
+    pub const NONE: Self = Self::new(1);
+
+    /// Returns a new retry policy which makes up to `max_attempts` attempts
+    /// (including the initial attempt), with no backoff between them.
+    ///
+    /// A `max_attempts` of 0 is treated as 1.
+    #[must_use]
+    pub const fn new(max_attempts: u8) -> Self {
+        Self {
+            max_attempts,
+            backoff_ms: 0,
+            backoff_multiplier: 1,
+        }
+    }
+
+    /// Sets the time to wait before the first retry, in milliseconds.
+    #[inline]
+    #[must_use]
+    pub const fn with_backoff_ms(self, backoff_ms: u32) -> Self {
+        Self { backoff_ms, ..self }
+    }
+
+    /// Sets the factor by which the backoff is multiplied after each retry.
+    ///
+    /// By default, the multiplier is 1, and the backoff is constant.
+    #[inline]
+    #[must_use]
+    pub const fn with_backoff_multiplier(self, backoff_multiplier: u32) -> Self {
+        Self {
+            backoff_multiplier,
+            ..self
+        }
+    }
+
+    /// Returns the maximum number of attempts permitted by this policy,
+    /// including the initial attempt.
+    #[must_use]
+    pub const fn max_attempts(&self) -> u8 {
+        if self.max_attempts == 0 {
+            1
+        } else {
+            self.max_attempts
+        }
+    }
+
+    /// Returns the backoff before the `retry`th retry (starting at 1), in
+    /// milliseconds.
+    pub(crate) fn backoff_ms(&self, retry: u8) -> u32 {
+        let mut backoff = self.backoff_ms;
+        for _ in 1..retry {
+            backoff = backoff.saturating_mul(self.backoff_multiplier);
+        }
+        backoff
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+// === impl RetryStats ===
+
+impl RetryStats {
+    pub(crate) const fn new() -> Self {
+        Self {
+            retries: 0,
+            recovered: 0,
+            exhausted: 0,
+        }
+    }
+
+    /// Records the outcome of a command which was attempted `attempts` times.
+    ///
+    /// `exhausted` is `true` if the command failed with a retriable error on
+    /// the last attempt permitted by the retry policy. Commands which failed
+    /// with an error that is not retriable are not counted as exhausted.
+    pub(crate) fn record(&mut self, attempts: u8, succeeded: bool, exhausted: bool) {
+        let retries = u32::from(attempts.saturating_sub(1));
+        self.retries = self.retries.saturating_add(retries);
+        if succeeded {
+            if retries > 0 {
+                self.recovered = self.recovered.saturating_add(1);
+            }
+        } else if exhausted {
+            self.exhausted = self.exhausted.saturating_add(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_counts_retries_and_recoveries() {
+        let mut stats = RetryStats::new();
+        stats.record(1, true, false);
+        stats.record(3, true, false);
+        assert_eq!(stats.retries, 2);
+        assert_eq!(stats.recovered, 1);
+        assert_eq!(stats.exhausted, 0);
+    }
+
+    #[test]
+    fn record_counts_only_exhausted_failures() {
+        let mut stats = RetryStats::new();
+        // A non-retriable error on the first attempt.
+        stats.record(1, false, false);
+        // A non-retriable error after a retry.
+        stats.record(2, false, false);
+        assert_eq!(stats.exhausted, 0);
+
+        stats.record(3, false, true);
+        assert_eq!(stats.retries, 3);
+        assert_eq!(stats.exhausted, 1);
+        assert_eq!(stats.recovered, 0);
+    }
+}
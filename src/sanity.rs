@@ -0,0 +1,318 @@
+use crate::{Channel, Measurements};
+
+bitflags::bitflags! {
+    /// Anomalies detected in a [`Measurements`] by a [`SanityChecker`].
+    #[derive(Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "fmt", derive(Debug))]
+    pub struct Anomalies: u8 {
+        /// A reading has not changed for an implausibly long time.
+        ///
+        /// This may indicate that the sensor has failed, such as a seized fan
+        /// before the sensor's `FAN_ERROR` status bit is set.
+        const STUCK = 1 << 0;
+        /// A reading changed implausibly quickly since the previous sample.
+        const JUMP = 1 << 1;
+        /// A reading is outside the sensor's specified measurement range.
+        const OUT_OF_RANGE = 1 << 2;
+        /// Readings from different channels contradict each other, such as a
+        /// PM<sub>1.0</sub> concentration greater than the PM<sub>2.5</sub>
+        /// concentration.
+        const INCONSISTENT = 1 << 3;
+    }
+}
+
+/// Detects implausible [`Measurements`], such as stuck or out-of-range
+/// readings.
+///
+/// Each call to [`check()`](Self::check) compares a new sample against the
+/// sensor's specified measurement ranges and the previous samples, returning
+/// the [`Anomalies`] found. The anomalies for each individual [`Channel`] in
+/// the most recent sample are available from
+/// [`channel_anomalies()`](Self::channel_anomalies).
+///
+/// Limits are configured in each channel's raw units, as sent on the wire. See
+/// the [`Measurements`] documentation for the scale factor of each channel.
+#[derive(Clone)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct SanityChecker {
+    stuck_samples: u32,
+    stuck_baselines: [Option<i32>; 8],
+    max_jump: [u32; 8],
+    prev: [Option<i32>; 8],
+    unchanged: [u32; 8],
+    anomalies: [Anomalies; 8],
+}
+
+/// The specified measurement range of each channel, in raw units.
+const RANGES: [(i32, i32); 8] = [
+    // PM concentrations: 0-1000 µg/m³.
+    (0, 1000 * 10),
+    (0, 1000 * 10),
+    (0, 1000 * 10),
+    (0, 1000 * 10),
+    // Relative humidity: 0-100 %RH.
+    (0, 100 * 100),
+    // Temperature: -10-50 °C.
+    (-10 * 200, 50 * 200),
+    // VOC and NOx indices: 1-500.
+    (10, 500 * 10),
+    (10, 500 * 10),
+];
+
+// === impl SanityChecker ===
+
+impl SanityChecker {
+    /// The default number of consecutive identical samples after which a
+    /// reading is considered stuck (10 minutes of 1 Hz samples).
+    pub const DEFAULT_STUCK_SAMPLES: u32 = 600;
+
+    /// The default raw value of each channel which is never considered
+    /// [stuck](Anomalies::STUCK), since it is expected for long periods.
+    ///
+    /// These are a VOC index of 100, and a NOx index of 1 (the baselines
+    /// reported while gas levels are at their learned average). Particulate
+    /// matter concentrations have no baseline, since a seized fan or failed
+    /// laser may report a constant concentration of zero.
+    pub const DEFAULT_STUCK_BASELINES: [Option<i32>; 8] =
+        [None, None, None, None, None, None, Some(100 * 10), Some(10)];
+
+    /// The default maximum change between consecutive samples, in raw units:
+    /// 200 µg/m³ for PM concentrations, 10 %RH, 5 °C, and 100 index points.
+    pub const DEFAULT_MAX_JUMP: [u32; 8] = [
+        200 * 10,
+        200 * 10,
+        200 * 10,
+        200 * 10,
+        10 * 100,
+        5 * 200,
+        100 * 10,
+        100 * 10,
+    ];
+
+    /// Returns a new `SanityChecker` using the default limits.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            stuck_samples: Self::DEFAULT_STUCK_SAMPLES,
+            stuck_baselines: Self::DEFAULT_STUCK_BASELINES,
+            max_jump: Self::DEFAULT_MAX_JUMP,
+            prev: [None; 8],
+            unchanged: [0; 8],
+            anomalies: [Anomalies::empty(); 8],
+        }
+    }
+
+    /// Set the number of consecutive identical samples after which a reading
+    /// is considered [stuck](Anomalies::STUCK).
+    ///
+    /// A value of 0 disables stuck-value detection. A single sample cannot be
+    /// stuck, so a value of 1 is treated as 2.
+    #[inline]
+    #[must_use]
+    pub const fn with_stuck_samples(mut self, samples: u32) -> Self {
+        self.stuck_samples = if samples == 1 { 2 } else { samples };
+        self
+    }
+
+    /// Set the raw value of `channel` which is never considered
+    /// [stuck](Anomalies::STUCK), or [`None`] to check every value.
+    ///
+    /// By default, [`DEFAULT_STUCK_BASELINES`](Self::DEFAULT_STUCK_BASELINES)
+    /// are used. If the sensor's VOC or NOx algorithm is tuned with a
+    /// different index offset, its baseline should be changed to match.
+    ///
+    /// In very clean air, such as in a clean room, the particulate matter
+    /// concentrations may read zero for long periods. A baseline of `Some(0)`
+    /// for the PM channels avoids reporting them as stuck, at the cost of no
+    /// longer detecting a failed fan or laser which reads zero.
+    #[inline]
+    #[must_use]
+    pub const fn with_stuck_baseline(mut self, channel: Channel, baseline: Option<i32>) -> Self {
+        self.stuck_baselines[channel.index()] = baseline;
+        self
+    }
+
+    /// Set the maximum change in `channel`'s raw reading between consecutive
+    /// samples, above which a [jump](Anomalies::JUMP) is reported.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_jump(mut self, channel: Channel, max_jump: u32) -> Self {
+        self.max_jump[channel.index()] = max_jump;
+        self
+    }
+
+    /// Checks a new sample, returning the [`Anomalies`] detected in any
+    /// channel.
+    ///
+    /// Channels which are not present in `measurements` are not checked, and
+    /// do not reset stuck-value detection.
+    ///
+    /// A reading equal to its channel's
+    /// [stuck baseline](Self::with_stuck_baseline) is never considered stuck.
+    pub fn check(&mut self, measurements: &Measurements) -> Anomalies {
+        let mut all = Anomalies::empty();
+        for channel in Channel::ALL {
+            let i = channel.index();
+            let mut anomalies = Anomalies::empty();
+            if let Some(value) = channel.raw_value(measurements) {
+                let (min, max) = RANGES[i];
+                if value < min || value > max {
+                    anomalies |= Anomalies::OUT_OF_RANGE;
+                }
+
+                match self.prev[i] {
+                    Some(prev) if prev == value => {
+                        self.unchanged[i] = self.unchanged[i].saturating_add(1);
+                    }
+                    Some(prev) => {
+                        self.unchanged[i] = 0;
+                        if prev.abs_diff(value) > self.max_jump[i] {
+                            anomalies |= Anomalies::JUMP;
+                        }
+                    }
+                    None => self.unchanged[i] = 0,
+                }
+                self.prev[i] = Some(value);
+
+                // `unchanged` counts repeats, so N identical samples have
+                // N - 1 repeats.
+                if self.stuck_samples > 0
+                    && self.unchanged[i] >= self.stuck_samples - 1
+                    && self.stuck_baselines[i] != Some(value)
+                {
+                    anomalies |= Anomalies::STUCK;
+                }
+            }
+            self.anomalies[i] = anomalies;
+            all |= anomalies;
+        }
+
+        // PM concentrations are cumulative, so each size bin must include the
+        // smaller ones.
+        const PM: [Channel; 4] = [
+            Channel::Pm1_0,
+            Channel::Pm2_5,
+            Channel::Pm4_0,
+            Channel::Pm10_0,
+        ];
+        for pair in PM.windows(2) {
+            let (Some(smaller), Some(larger)) = (
+                pair[0].raw_value(measurements),
+                pair[1].raw_value(measurements),
+            ) else {
+                continue;
+            };
+            if smaller > larger {
+                self.anomalies[pair[0].index()] |= Anomalies::INCONSISTENT;
+                self.anomalies[pair[1].index()] |= Anomalies::INCONSISTENT;
+                all |= Anomalies::INCONSISTENT;
+            }
+        }
+
+        all
+    }
+
+    /// Returns the [`Anomalies`] detected in `channel` by the most recent call
+    /// to [`check()`](Self::check).
+    #[must_use]
+    pub fn channel_anomalies(&self, channel: Channel) -> Anomalies {
+        self.anomalies[channel.index()]
+    }
+
+    /// Resets the history used to detect stuck readings and jumps.
+    pub fn reset(&mut self) {
+        self.prev = [None; 8];
+        self.unchanged = [0; 8];
+        self.anomalies = [Anomalies::empty(); 8];
+    }
+}
+
+impl Default for SanityChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns measurements with baseline gas indices, and particulate matter,
+    /// temperature, and humidity readings which change with `step`.
+    fn sample(step: u16) -> Measurements {
+        Measurements::from_raw_words([step, step, step, step, 4500 + step, 4000 + step, 1000, 10])
+    }
+
+    /// Returns measurements with no particulate matter, and a temperature and
+    /// humidity which change with `step`.
+    fn clean_air(step: u16) -> Measurements {
+        Measurements::from_raw_words([0, 0, 0, 0, 4500 + step, 4000 + step, 1000, 10])
+    }
+
+    #[test]
+    fn baselines_are_not_stuck() {
+        let mut checker = SanityChecker::new().with_stuck_samples(3);
+        for step in 0..10 {
+            assert!(checker.check(&sample(step)) == Anomalies::empty());
+        }
+    }
+
+    #[test]
+    fn stuck_after_identical_samples() {
+        let mut checker = SanityChecker::new().with_stuck_samples(3);
+        assert!(checker.check(&sample(0)) == Anomalies::empty());
+        assert!(checker.check(&sample(0)) == Anomalies::empty());
+        assert!(checker.check(&sample(0)) == Anomalies::STUCK);
+        assert!(checker.channel_anomalies(Channel::Temperature) == Anomalies::STUCK);
+        assert!(checker.channel_anomalies(Channel::RelativeHumidity) == Anomalies::STUCK);
+        assert!(checker.channel_anomalies(Channel::VocIndex) == Anomalies::empty());
+        assert!(checker.check(&sample(1)) == Anomalies::empty());
+    }
+
+    #[test]
+    fn stuck_baseline_is_configurable() {
+        let mut checker = SanityChecker::new()
+            .with_stuck_samples(2)
+            .with_stuck_baseline(Channel::VocIndex, None);
+        checker.check(&sample(0));
+        checker.check(&sample(1));
+        assert!(checker.channel_anomalies(Channel::VocIndex) == Anomalies::STUCK);
+        assert!(checker.channel_anomalies(Channel::NoxIndex) == Anomalies::empty());
+    }
+
+    #[test]
+    fn zero_pm_is_stuck_by_default() {
+        let mut checker = SanityChecker::new().with_stuck_samples(3);
+        checker.check(&clean_air(0));
+        checker.check(&clean_air(1));
+        assert!(checker.check(&clean_air(2)) == Anomalies::STUCK);
+        assert!(checker.channel_anomalies(Channel::Pm2_5) == Anomalies::STUCK);
+        assert!(checker.channel_anomalies(Channel::Temperature) == Anomalies::empty());
+
+        // In a clean room, zero may be configured as a baseline.
+        let mut checker = SanityChecker::new().with_stuck_samples(3);
+        for channel in [
+            Channel::Pm1_0,
+            Channel::Pm2_5,
+            Channel::Pm4_0,
+            Channel::Pm10_0,
+        ] {
+            checker = checker.with_stuck_baseline(channel, Some(0));
+        }
+        for step in 0..10 {
+            assert!(checker.check(&clean_air(step)) == Anomalies::empty());
+        }
+    }
+
+    #[test]
+    fn single_stuck_sample_is_clamped() {
+        let mut checker = SanityChecker::new().with_stuck_samples(1);
+        assert!(checker.check(&sample(0)) == Anomalies::empty());
+        assert!(checker.check(&sample(0)) == Anomalies::STUCK);
+
+        let mut checker = SanityChecker::new().with_stuck_samples(0);
+        for _ in 0..10 {
+            assert!(checker.check(&sample(0)) == Anomalies::empty());
+        }
+    }
+}
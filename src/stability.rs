@@ -0,0 +1,113 @@
+use crate::ParticulateMode;
+
+/// How settled a sensor's readings are, following the start of measurement.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub enum Stability {
+    /// Measurement started recently, and readings are absent or not yet
+    /// meaningful.
+    WarmingUp,
+    /// Readings are present, but are still being adjusted by the sensor's
+    /// learning algorithms, and may be inaccurate.
+    Learning,
+    /// Readings are stable.
+    Stable,
+}
+
+/// The [`Stability`] of each of the sensor's measurement channels.
+///
+/// This is computed by the drivers from the time elapsed since measurement was
+/// started, read from the driver's [`Clock`](crate::Clock). Without a clock,
+/// the elapsed time is estimated from the number of new measurements read
+/// since measurement was started, with each measurement counting as one
+/// second. Measurements read without first waiting for new data (such as with
+/// `read_measurements()` alone) are not counted, so without a clock, the
+/// reported stability may lag behind the sensor's actual state.
+///
+/// Temperature and humidity readings are available immediately after
+/// measurement starts, and so are not tracked.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[non_exhaustive]
+pub struct ChannelStability {
+    /// The stability of the particulate matter readings.
+    ///
+    /// Particulate matter readings require about 30 seconds after the fan
+    /// starts to stabilize. They never become stable while particulate matter
+    /// measurement is disabled.
+    pub pm: Stability,
+    /// The stability of the VOC index.
+    ///
+    /// The VOC index is not meaningful for the first 45 seconds of
+    /// measurement, and its learning phase lasts for about 1.45 hours.
+    pub voc: Stability,
+    /// The stability of the NOx index.
+    ///
+    /// The NOx index is absent for the first 10 seconds of measurement, is not
+    /// meaningful for the first 45 seconds, and its learning phase lasts for
+    /// about 5.7 hours.
+    pub nox: Stability,
+}
+
+/// The time after the fan starts for particulate matter readings to stabilize.
+const PM_WARMUP_S: u32 = 30;
+
+/// The initial blackout period of the gas index algorithm, during which the
+/// VOC and NOx indices are not meaningful.
+const GAS_INDEX_WARMUP_S: u32 = 45;
+
+/// The initialization durations of the gas index algorithm's mean and variance
+/// estimators, after which the VOC and NOx indices are considered stable.
+const VOC_LEARNING_S: u32 = 5220;
+const NOX_LEARNING_S: u32 = 20520;
+
+// === impl ChannelStability ===
+
+impl ChannelStability {
+    /// Every channel is [`Stability::Stable`].
+    pub const STABLE: Self = Self {
+        pm: Stability::Stable,
+        voc: Stability::Stable,
+        nox: Stability::Stable,
+    };
+
+    /// Returns the stability of each channel after measuring in the provided
+    /// [`ParticulateMode`] for `elapsed_s` seconds.
+    #[must_use]
+    pub(crate) const fn after(elapsed_s: u32, particulates: ParticulateMode) -> Self {
+        let pm = match particulates {
+            ParticulateMode::Enabled if elapsed_s >= PM_WARMUP_S => Stability::Stable,
+            _ => Stability::WarmingUp,
+        };
+        Self {
+            pm,
+            voc: gas_index(elapsed_s, VOC_LEARNING_S),
+            nox: gas_index(elapsed_s, NOX_LEARNING_S),
+        }
+    }
+
+    /// Returns `true` if every channel is [`Stability::Stable`].
+    #[must_use]
+    pub const fn is_stable(&self) -> bool {
+        matches!(
+            (self.pm, self.voc, self.nox),
+            (Stability::Stable, Stability::Stable, Stability::Stable)
+        )
+    }
+}
+
+impl Default for ChannelStability {
+    fn default() -> Self {
+        Self::STABLE
+    }
+}
+
+const fn gas_index(elapsed_s: u32, learning_s: u32) -> Stability {
+    if elapsed_s < GAS_INDEX_WARMUP_S {
+        Stability::WarmingUp
+    } else if elapsed_s < learning_s {
+        Stability::Learning
+    } else {
+        Stability::Stable
+    }
+}
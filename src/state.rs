@@ -0,0 +1,361 @@
+use crate::{
+    fan_cleaning,
+    msg::{Measurements, SensorStatus},
+    ChannelStability, Diagnostics, Error, Instant, Mode, ParticulateMode, RetryPolicy, Sen5xConfig,
+    I2C_ADDR,
+};
+
+/// The state of a single sensor, independent of the I²C bus used to
+/// communicate with it.
+///
+/// A [`Sen5x`](crate::Sen5x) or [`Sen5xAsync`](crate::Sen5xAsync) driver
+/// created with [`new()`](crate::Sen5x::new) owns both its I²C bus and its
+/// `State`. When the bus is shared with other devices, the `State` may instead
+/// be stored separately, and a driver constructed for each operation from a
+/// borrowed bus and a borrowed `State`, using
+/// [`from_parts()`](crate::Sen5x::from_parts), such as
+/// `Sen5x::from_parts(&mut i2c, &mut state)`.
+///
+/// The state records whether the sensor is measuring, along with the
+/// driver's configuration and [`Diagnostics`], so it must be preserved
+/// between operations on the same sensor.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct State {
+    pub(crate) mode: Mode,
+    pub(crate) particulates: ParticulateMode,
+    pub(crate) addr: u8,
+    pub(crate) retry: RetryPolicy,
+    pub(crate) diagnostics: Diagnostics,
+    pub(crate) config: Sen5xConfig,
+    /// The time at which measurement was started, if the driver has a clock.
+    started_at: Option<Instant>,
+    /// The number of new measurements read since measurement was started.
+    samples: u32,
+    /// Whether the sensor has reported that a new measurement is ready, which
+    /// has not yet been read.
+    fresh: bool,
+    /// The fan cleaning procedure believed to be running, if any.
+    pub(crate) fan_cleaning: Option<FanCleaning>,
+    /// The time at which the last command was sent and its execution time in
+    /// milliseconds, if the driver has a clock and has not yet waited for it
+    /// to execute.
+    pub(crate) executing: Option<(Instant, u32)>,
+}
+
+/// A fan cleaning procedure which was started through the driver, or observed
+/// in the sensor's status register.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub(crate) struct FanCleaning {
+    /// The time at which cleaning was started or observed, if the driver has
+    /// a clock.
+    since: Option<Instant>,
+    /// The number of new measurements read since then.
+    samples: u32,
+}
+
+// === impl State ===
+
+impl State {
+    /// Returns a new `State` for a sensor at the default I²C address (`0x69`)
+    /// which is not currently measuring.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            mode: Mode::Idle,
+            particulates: ParticulateMode::Enabled,
+            addr: I2C_ADDR,
+            retry: RetryPolicy::NONE,
+            diagnostics: Diagnostics::new(),
+            config: Sen5xConfig::EMPTY,
+            started_at: None,
+            samples: 0,
+            fresh: false,
+            fan_cleaning: None,
+            executing: None,
+        }
+    }
+
+    /// Set the I²C address of the sensor.
+    #[inline]
+    #[must_use]
+    pub const fn with_i2c_address(mut self, addr: u8) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    /// Set the [`RetryPolicy`] used when reading from the sensor.
+    #[inline]
+    #[must_use]
+    pub const fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Returns the sensor's current [`Mode`].
+    #[inline]
+    #[must_use]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the [`ParticulateMode`] the sensor was last started in.
+    #[inline]
+    #[must_use]
+    pub const fn particulate_mode(&self) -> ParticulateMode {
+        self.particulates
+    }
+
+    /// Returns the I²C address of the sensor.
+    #[inline]
+    #[must_use]
+    pub const fn i2c_address(&self) -> u8 {
+        self.addr
+    }
+
+    /// Returns [`Diagnostics`] counters tracking the health of communication
+    /// with the sensor.
+    #[inline]
+    #[must_use]
+    pub const fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    /// Returns the [`Sen5xConfig`] applied to the sensor through the driver,
+    /// which is written back to the sensor by `reapply_config()`.
+    #[inline]
+    #[must_use]
+    pub const fn config(&self) -> &Sen5xConfig {
+        &self.config
+    }
+
+    /// Returns `true` if the sensor's fan cleaning procedure is believed to be
+    /// running.
+    ///
+    /// This is set when fan cleaning is started through the driver, and
+    /// updated whenever the sensor's status register is read. Since the
+    /// procedure takes about 10 seconds, it is assumed to have completed once
+    /// 11 seconds have elapsed according to the driver's
+    /// [`Clock`](crate::Clock), or, without a clock, once 11 new measurements
+    /// have been read.
+    #[inline]
+    #[must_use]
+    pub const fn is_fan_cleaning(&self) -> bool {
+        self.fan_cleaning.is_some()
+    }
+
+    /// Returns the [`ChannelStability`] of the sensor's readings at `now`,
+    /// based on how long it has been measuring.
+    ///
+    /// If measurement was started by a driver with a [`Clock`](crate::Clock),
+    /// the elapsed time is measured from then until `now`, which should be
+    /// read from the same clock. Otherwise, or if `now` is [`None`], the
+    /// elapsed time is estimated from the number of new measurements read
+    /// since measurement was started, each counting as one second. That
+    /// estimate lags behind the sensor's actual state when measurements are
+    /// not read every second, such as while a duty cycle waits out its warm-up
+    /// with a delay.
+    ///
+    /// If the sensor is idle, every channel is
+    /// [`WarmingUp`](crate::Stability::WarmingUp).
+    #[must_use]
+    pub fn stability(&self, now: Option<Instant>) -> ChannelStability {
+        let elapsed_s = match (self.mode, self.started_at, now) {
+            (Mode::Idle, _, _) => 0,
+            (Mode::Measuring, Some(started_at), Some(now)) => {
+                u32::try_from(now.millis_since(started_at) / 1000).unwrap_or(u32::MAX)
+            }
+            (Mode::Measuring, _, _) => self.samples,
+        };
+        ChannelStability::after(elapsed_s, self.particulates)
+    }
+
+    /// Records that measurement was started in the provided
+    /// [`ParticulateMode`], at `now` if the driver has a clock.
+    pub(crate) fn started(&mut self, particulates: ParticulateMode, now: Option<Instant>) {
+        self.mode = Mode::Measuring;
+        self.particulates = particulates;
+        self.started_at = now;
+        self.samples = 0;
+        self.fresh = false;
+        self.fan_cleaning = None;
+    }
+
+    /// Records that fan cleaning was started at `now`.
+    pub(crate) fn fan_cleaning_started(&mut self, now: Option<Instant>) {
+        self.fan_cleaning = Some(FanCleaning {
+            since: now,
+            samples: 0,
+        });
+    }
+
+    /// Records the sensor's data-ready flag.
+    pub(crate) fn data_ready(&mut self, ready: bool) {
+        self.fresh |= ready;
+    }
+
+    /// Records that measurements were read at `now`, counting them towards the
+    /// sensor's warm-up if the sensor had reported that they were new, and
+    /// annotates them with the sensor's current state.
+    pub(crate) fn measurements_read(
+        &mut self,
+        measurements: Measurements,
+        now: Option<Instant>,
+    ) -> Measurements {
+        let fresh = core::mem::take(&mut self.fresh);
+        if fresh {
+            self.samples = self.samples.saturating_add(1);
+        }
+        if let Some(cleaning) = &mut self.fan_cleaning {
+            if fresh {
+                cleaning.samples = cleaning.samples.saturating_add(1);
+            }
+            if cleaning.is_done(now) {
+                self.fan_cleaning = None;
+            }
+        }
+        measurements
+            .with_particulate_mode(self.particulates)
+            .with_stability(self.stability(now))
+            .with_fan_cleaning(self.is_fan_cleaning())
+    }
+
+    /// Records the sensor's device status register, read at `now`.
+    pub(crate) fn status_read(&mut self, status: SensorStatus, now: Option<Instant>) {
+        if !status.contains(SensorStatus::FAN_CLEANING) {
+            self.fan_cleaning = None;
+        } else if self.fan_cleaning.is_none() {
+            self.fan_cleaning_started(now);
+        }
+    }
+
+    /// Returns the time at which the last command sent will have finished
+    /// executing, if the driver has not yet waited for it.
+    pub(crate) fn executing_until(&self) -> Option<Instant> {
+        self.executing
+            .map(|(sent_at, execution_ms)| sent_at.add_millis(u64::from(execution_ms)))
+    }
+
+    pub(crate) fn check_mode<E>(&mut self, expected: Mode) -> Result<(), Error<E>> {
+        self.mode
+            .check(expected)
+            .inspect_err(|_| self.diagnostics.mode_error())
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// === impl FanCleaning ===
+
+impl FanCleaning {
+    /// Returns `true` if the cleaning procedure has completed by `now`.
+    fn is_done(&self, now: Option<Instant>) -> bool {
+        match (self.since, now) {
+            (Some(since), Some(now)) => {
+                now.millis_since(since) >= u64::from(fan_cleaning::DURATION_MS)
+            }
+            _ => self.samples >= fan_cleaning::DURATION_MS / 1000,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Stability;
+
+    fn read_fresh(state: &mut State, now: Option<Instant>) -> Measurements {
+        state.data_ready(true);
+        state.measurements_read(Measurements::builder().build(), now)
+    }
+
+    #[test]
+    fn stability_from_clock() {
+        let mut state = State::new();
+        let start = Instant::from_millis(5000);
+        state.started(ParticulateMode::Enabled, Some(start));
+
+        // Only one measurement was read, but the clock shows that the sensor
+        // has been measuring for long enough.
+        let measurements = read_fresh(&mut state, Some(start.add_millis(60_000)));
+        assert!(measurements.stability().pm == Stability::Stable);
+        assert!(measurements.stability().voc == Stability::Learning);
+
+        let stability = state.stability(Some(start.add_millis(29_999)));
+        assert!(stability.pm == Stability::WarmingUp);
+    }
+
+    #[test]
+    fn stability_from_samples_without_clock() {
+        let mut state = State::new();
+        state.started(ParticulateMode::Enabled, None);
+        for _ in 0..29 {
+            read_fresh(&mut state, None);
+        }
+        assert!(state.stability(None).pm == Stability::WarmingUp);
+
+        // A measurement which was not reported as new is not counted.
+        state.measurements_read(Measurements::builder().build(), None);
+        assert!(state.stability(None).pm == Stability::WarmingUp);
+
+        let measurements = read_fresh(&mut state, None);
+        assert!(measurements.stability().pm == Stability::Stable);
+    }
+
+    #[test]
+    fn stability_when_idle() {
+        let mut state = State::new();
+        state.started(ParticulateMode::Enabled, Some(Instant::from_millis(0)));
+        state.mode = Mode::Idle;
+        let stability = state.stability(Some(Instant::from_millis(u64::MAX)));
+        assert!(stability.pm == Stability::WarmingUp);
+        assert!(stability.voc == Stability::WarmingUp);
+    }
+
+    #[test]
+    fn fan_cleaning_ends_by_clock() {
+        let mut state = State::new();
+        let start = Instant::from_millis(1000);
+        state.started(ParticulateMode::Enabled, Some(start));
+        state.fan_cleaning_started(Some(start));
+
+        let measurements = read_fresh(&mut state, Some(start.add_millis(10_999)));
+        assert!(measurements.during_fan_cleaning());
+        let measurements = read_fresh(&mut state, Some(start.add_millis(11_000)));
+        assert!(!measurements.during_fan_cleaning());
+        assert!(!state.is_fan_cleaning());
+    }
+
+    #[test]
+    fn fan_cleaning_ends_by_samples_without_clock() {
+        let mut state = State::new();
+        state.started(ParticulateMode::Enabled, None);
+        state.fan_cleaning_started(None);
+        for _ in 0..10 {
+            assert!(read_fresh(&mut state, None).during_fan_cleaning());
+        }
+        assert!(!read_fresh(&mut state, None).during_fan_cleaning());
+    }
+
+    #[test]
+    fn fan_cleaning_from_status() {
+        let mut state = State::new();
+        let start = Instant::from_millis(0);
+        state.started(ParticulateMode::Enabled, Some(start));
+        state.status_read(SensorStatus::FAN_CLEANING, Some(start));
+        // Reading the status again does not restart the timer.
+        state.status_read(SensorStatus::FAN_CLEANING, Some(start.add_millis(5000)));
+        assert!(read_fresh(&mut state, Some(start.add_millis(10_000))).during_fan_cleaning());
+        assert!(!read_fresh(&mut state, Some(start.add_millis(11_000))).during_fan_cleaning());
+
+        state.status_read(SensorStatus::FAN_CLEANING, Some(start.add_millis(12_000)));
+        assert!(state.is_fan_cleaning());
+        state.status_read(SensorStatus::empty(), Some(start.add_millis(13_000)));
+        assert!(!state.is_fan_cleaning());
+    }
+}
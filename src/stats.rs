@@ -0,0 +1,303 @@
+use crate::{Channel, Measurements};
+
+/// Rolling statistics over a window of the last `N` [`Measurements`].
+///
+/// For each [`Channel`], this tracks the minimum, maximum, mean, and standard
+/// deviation of the readings in the window, and an exponential moving average
+/// of all readings. Readings which are not present in a [`Measurements`] are
+/// skipped, so a channel's statistics only include the samples in which it was
+/// present.
+///
+/// The window is stored as the measurements' raw words, taking 16 bytes per
+/// sample. Since the sensor produces a measurement once per second, a window of
+/// `N = 60` provides 1-minute aggregates, and `N = 900` provides 15-minute
+/// aggregates.
+#[derive(Clone)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct MeasurementStats<const N: usize> {
+    window: [[u16; 8]; N],
+    /// The index of the oldest sample in the window.
+    head: usize,
+    len: usize,
+    ema_alpha: f32,
+    ema: [Option<f32>; 8],
+}
+
+/// Statistics for a single [`Channel`] over a [`MeasurementStats`] window.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[non_exhaustive]
+pub struct ChannelStats {
+    /// The number of samples in the window in which the channel was present.
+    pub count: usize,
+    /// The minimum reading.
+    pub min: f32,
+    /// The maximum reading.
+    pub max: f32,
+    /// The mean reading.
+    pub mean: f32,
+    /// The population standard deviation of the readings.
+    pub std_dev: f32,
+}
+
+// === impl MeasurementStats ===
+
+impl<const N: usize> MeasurementStats<N> {
+    /// Returns a new, empty `MeasurementStats`.
+    ///
+    /// The exponential moving averages use a smoothing factor of `2 / (N + 1)`,
+    /// giving them a similar center of mass to the window's mean. Use
+    /// [`with_ema_alpha()`](Self::with_ema_alpha) to change this.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            window: [[0; 8]; N],
+            head: 0,
+            len: 0,
+            ema_alpha: 2.0 / (N as f32 + 1.0),
+            ema: [None; 8],
+        }
+    }
+
+    /// Sets the smoothing factor of the exponential moving averages.
+    ///
+    /// Each new reading is weighted by `alpha`, which is clamped to the range
+    /// `0.0..=1.0`. Larger values respond more quickly to changes.
+    #[must_use]
+    pub fn with_ema_alpha(mut self, alpha: f32) -> Self {
+        self.ema_alpha = alpha.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Adds a new sample, evicting the oldest sample if the window is full.
+    pub fn push(&mut self, measurements: &Measurements) {
+        if N == 0 {
+            self.update_ema(measurements);
+            return;
+        }
+        let words = measurements.to_raw_words();
+        if self.len < N {
+            self.window[(self.head + self.len) % N] = words;
+            self.len += 1;
+        } else {
+            self.window[self.head] = words;
+            self.head = (self.head + 1) % N;
+        }
+        self.update_ema(measurements);
+    }
+
+    /// Removes all samples, and resets the exponential moving averages.
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+        self.ema = [None; 8];
+    }
+
+    /// Returns the number of samples in the window.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the window contains no samples.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the window contains `N` samples.
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Returns statistics for `channel` over the window, or [`None`] if the
+    /// channel was not present in any sample in the window.
+    #[must_use]
+    pub fn stats(&self, channel: Channel) -> Option<ChannelStats> {
+        let mut count = 0;
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        let mut sum = 0.0;
+        for value in self.values(channel) {
+            count += 1;
+            min = min.min(value);
+            max = max.max(value);
+            sum += value;
+        }
+        if count == 0 {
+            return None;
+        }
+        let mean = sum / count as f32;
+        let variance = self
+            .values(channel)
+            .map(|value| (value - mean) * (value - mean))
+            .sum::<f32>()
+            / count as f32;
+        Some(ChannelStats {
+            count,
+            min,
+            max,
+            mean,
+            std_dev: libm::sqrtf(variance),
+        })
+    }
+
+    /// Returns the exponential moving average of `channel`, or [`None`] if the
+    /// channel has not been present in any sample since the last
+    /// [`clear()`](Self::clear).
+    #[must_use]
+    pub fn ema(&self, channel: Channel) -> Option<f32> {
+        self.ema[channel.index()]
+    }
+
+    /// Returns a [`Measurements`] containing the mean of each channel over the
+    /// window.
+    ///
+    /// Channels which were not present in any sample in the window are not
+    /// present in the returned `Measurements`.
+    #[must_use]
+    pub fn mean(&self) -> Measurements {
+        let mean = |channel| self.stats(channel).map(|stats| stats.mean);
+        let mut builder = Measurements::builder();
+        if let Some(value) = mean(Channel::Pm1_0) {
+            builder = builder.pm1_0(value);
+        }
+        if let Some(value) = mean(Channel::Pm2_5) {
+            builder = builder.pm2_5(value);
+        }
+        if let Some(value) = mean(Channel::Pm4_0) {
+            builder = builder.pm4_0(value);
+        }
+        if let Some(value) = mean(Channel::Pm10_0) {
+            builder = builder.pm10_0(value);
+        }
+        if let Some(value) = mean(Channel::RelativeHumidity) {
+            builder = builder.relative_humidity(value);
+        }
+        if let Some(value) = mean(Channel::Temperature) {
+            builder = builder.temp_c(value);
+        }
+        if let Some(value) = mean(Channel::VocIndex) {
+            builder = builder.voc_index(value);
+        }
+        if let Some(value) = mean(Channel::NoxIndex) {
+            builder = builder.nox_index(value);
+        }
+        builder.build()
+    }
+
+    /// Returns an iterator over the readings of `channel` in the window, from
+    /// oldest to newest, skipping samples in which it was not present.
+    fn values(&self, channel: Channel) -> impl Iterator<Item = f32> + '_ {
+        (0..self.len).filter_map(move |i| {
+            let words = self.window[(self.head + i) % N];
+            channel.value(&Measurements::from_raw_words(words))
+        })
+    }
+
+    fn update_ema(&mut self, measurements: &Measurements) {
+        for channel in Channel::ALL {
+            let Some(value) = channel.value(measurements) else {
+                continue;
+            };
+            let ema = &mut self.ema[channel.index()];
+            *ema = Some(match *ema {
+                Some(prev) => prev + (value - prev) * self.ema_alpha,
+                None => value,
+            });
+        }
+    }
+}
+
+impl<const N: usize> Default for MeasurementStats<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pm(pm1_0: f32) -> Measurements {
+        Measurements::builder().pm1_0(pm1_0).build()
+    }
+
+    #[test]
+    fn wrapping_window() {
+        let mut stats = MeasurementStats::<3>::new();
+        for value in [1.0, 2.0, 3.0, 4.0, 5.0] {
+            stats.push(&pm(value));
+        }
+        assert_eq!(stats.len(), 3);
+        assert!(stats.is_full());
+
+        // Only the last three samples remain in the window.
+        let pm1_0 = stats.stats(Channel::Pm1_0).unwrap();
+        assert_eq!(pm1_0.count, 3);
+        assert!(pm1_0.min == 3.0);
+        assert!(pm1_0.max == 5.0);
+        assert!((pm1_0.mean - 4.0).abs() < 0.001);
+        assert!((pm1_0.std_dev - libm::sqrtf(2.0 / 3.0)).abs() < 0.001);
+        assert!(stats.mean().pm1_0() == Some(4.0));
+    }
+
+    #[test]
+    fn empty_window() {
+        let mut stats = MeasurementStats::<3>::new();
+        assert!(stats.is_empty());
+        assert!(stats.stats(Channel::Pm1_0).is_none());
+        assert!(stats.ema(Channel::Pm1_0).is_none());
+        assert!(stats.mean() == Measurements::builder().build());
+
+        stats.push(&pm(1.0));
+        stats.clear();
+        assert!(stats.is_empty());
+        assert!(stats.stats(Channel::Pm1_0).is_none());
+        assert!(stats.ema(Channel::Pm1_0).is_none());
+    }
+
+    #[test]
+    fn missing_readings_are_not_counted() {
+        let mut stats = MeasurementStats::<4>::new();
+        stats.push(&Measurements::builder().pm1_0(1.0).temp_c(20.0).build());
+        stats.push(&pm(2.0));
+        stats.push(&pm(3.0));
+        stats.push(&Measurements::builder().pm1_0(4.0).temp_c(22.0).build());
+
+        // The two samples without a temperature are skipped, rather than
+        // counted as zero.
+        let temp = stats.stats(Channel::Temperature).unwrap();
+        assert_eq!(temp.count, 2);
+        assert!(temp.min == 20.0);
+        assert!(temp.max == 22.0);
+        assert!((temp.mean - 21.0).abs() < 0.001);
+        assert!((temp.std_dev - 1.0).abs() < 0.001);
+        assert_eq!(stats.stats(Channel::Pm1_0).unwrap().count, 4);
+        assert!(stats.stats(Channel::RelativeHumidity).is_none());
+    }
+
+    #[test]
+    fn ema() {
+        let mut stats = MeasurementStats::<3>::new().with_ema_alpha(0.25);
+
+        // The first reading seeds the average, rather than being weighted
+        // against zero.
+        stats.push(&pm(8.0));
+        assert!(stats.ema(Channel::Pm1_0) == Some(8.0));
+        stats.push(&pm(16.0));
+        assert!(stats.ema(Channel::Pm1_0) == Some(10.0));
+
+        // Samples without the channel leave its average unchanged.
+        stats.push(&Measurements::builder().temp_c(20.0).build());
+        assert!(stats.ema(Channel::Pm1_0) == Some(10.0));
+        assert!(stats.ema(Channel::Temperature) == Some(20.0));
+
+        // The default smoothing factor is 2 / (N + 1).
+        let mut stats = MeasurementStats::<3>::new();
+        stats.push(&pm(8.0));
+        stats.push(&pm(16.0));
+        assert!(stats.ema(Channel::Pm1_0) == Some(12.0));
+    }
+}
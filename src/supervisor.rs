@@ -0,0 +1,372 @@
+use crate::{Error, SensorStatus};
+
+/// Monitors the health of a sensor, and decides how to recover from failures.
+///
+/// A `Supervisor` is used with the drivers' `measure_supervised()` methods.
+/// Each failed measurement, whether caused by an I²C error, a response which
+/// could not be decoded, a timeout waiting for data, or an error bit in the
+/// sensor's [`SensorStatus`], escalates through increasingly drastic
+/// [`RecoveryAction`]s:
+///
+/// 1. [`Retry`](RecoveryAction::Retry) the measurement, up to
+///    [`max_retries`](Self::with_max_retries) times,
+/// 2. [`RestartMeasurement`](RecoveryAction::RestartMeasurement) in the
+///    previous [`ParticulateMode`](crate::ParticulateMode), up to
+///    [`max_restarts`](Self::with_max_restarts) times,
+/// 3. [`Reset`](RecoveryAction::Reset) the sensor, re-apply the configuration
+///    previously written by the driver, and restart measurement, up to
+///    [`max_resets`](Self::with_max_resets) times.
+///
+/// Errors reported in the sensor's status register are latched until the
+/// sensor is reset, so they escalate directly to a reset. Since the status
+/// register is only read periodically, measurements may succeed after a reset
+/// even though the error has recurred. The number of resets is therefore only
+/// cleared once the status register has been read without errors, so that
+/// `max_resets` also bounds repeated status errors.
+///
+/// If every action has been exhausted, the sensor is considered
+/// [`Failed`](Health::Failed), and no further measurements are attempted
+/// until the supervisor is [`reset()`](Self::reset). A successful measurement
+/// returns the supervisor to [`Healthy`](Health::Healthy).
+#[derive(Clone)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub struct Supervisor {
+    max_retries: u8,
+    max_restarts: u8,
+    max_resets: u8,
+    status_interval: u32,
+    data_timeout_ms: u32,
+    health: Health,
+    retries: u8,
+    restarts: u8,
+    resets: u8,
+    /// Whether the supervisor has recovered from a status error, and the
+    /// status register has not since been read without errors.
+    status_unverified: bool,
+    since_status: u32,
+    last_status: Option<SensorStatus>,
+    stats: RecoveryStats,
+}
+
+/// The health of a sensor, as determined by a [`Supervisor`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub enum Health {
+    /// The most recent measurement succeeded.
+    Healthy,
+    /// The most recent measurement failed, and the supervisor is attempting to
+    /// recover.
+    Recovering,
+    /// Every recovery action has been exhausted, and the sensor is considered
+    /// to have failed.
+    Failed,
+}
+
+/// An action taken by a [`Supervisor`] to recover from a failure.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+pub enum RecoveryAction {
+    /// Retry the failed measurement.
+    Retry,
+    /// Stop measurement, and start it again in the previous particulate mode.
+    RestartMeasurement,
+    /// Reset the sensor, re-apply its configuration, and start measurement in
+    /// the previous particulate mode.
+    Reset,
+}
+
+/// Counters tracking the recovery actions taken by a [`Supervisor`].
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[non_exhaustive]
+pub struct RecoveryStats {
+    /// The number of failed measurements.
+    pub failures: u32,
+    /// The number of measurements retried.
+    pub retries: u32,
+    /// The number of times measurement was restarted.
+    pub restarts: u32,
+    /// The number of times the sensor was reset.
+    pub resets: u32,
+    /// The number of times the sensor recovered after a failure.
+    pub recovered: u32,
+    /// The number of times the sensor was considered to have failed.
+    pub failed: u32,
+}
+
+// === impl Supervisor ===
+
+impl Supervisor {
+    /// The default number of times a failed measurement is retried.
+    pub const DEFAULT_MAX_RETRIES: u8 = 3;
+    /// The default number of times measurement is restarted after retries are
+    /// exhausted.
+    pub const DEFAULT_MAX_RESTARTS: u8 = 1;
+    /// The default number of times the sensor is reset after restarts are
+    /// exhausted.
+    pub const DEFAULT_MAX_RESETS: u8 = 3;
+    /// The default number of measurements between reads of the sensor's status
+    /// register (one minute of 1 Hz samples).
+    pub const DEFAULT_STATUS_INTERVAL: u32 = 60;
+    /// The default time to wait for a new measurement before it is considered
+    /// to have failed, in milliseconds.
+    pub const DEFAULT_DATA_TIMEOUT_MS: u32 = 3000;
+
+    /// Returns a new `Supervisor` using the default limits.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_retries: Self::DEFAULT_MAX_RETRIES,
+            max_restarts: Self::DEFAULT_MAX_RESTARTS,
+            max_resets: Self::DEFAULT_MAX_RESETS,
+            status_interval: Self::DEFAULT_STATUS_INTERVAL,
+            data_timeout_ms: Self::DEFAULT_DATA_TIMEOUT_MS,
+            health: Health::Healthy,
+            retries: 0,
+            restarts: 0,
+            resets: 0,
+            status_unverified: false,
+            since_status: 0,
+            last_status: None,
+            stats: RecoveryStats {
+                failures: 0,
+                retries: 0,
+                restarts: 0,
+                resets: 0,
+                recovered: 0,
+                failed: 0,
+            },
+        }
+    }
+
+    /// Set the number of times a failed measurement is retried before
+    /// measurement is restarted.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_retries(mut self, max_retries: u8) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the number of times measurement is restarted before the sensor is
+    /// reset.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_restarts(mut self, max_restarts: u8) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    /// Set the number of times the sensor is reset before it is considered to
+    /// have failed.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_resets(mut self, max_resets: u8) -> Self {
+        self.max_resets = max_resets;
+        self
+    }
+
+    /// Set the number of measurements between reads of the sensor's status
+    /// register.
+    ///
+    /// A value of 0 disables status checks.
+    #[inline]
+    #[must_use]
+    pub const fn with_status_interval(mut self, samples: u32) -> Self {
+        self.status_interval = samples;
+        self
+    }
+
+    /// Set the time to wait for a new measurement before it is considered to
+    /// have failed, in milliseconds.
+    #[inline]
+    #[must_use]
+    pub const fn with_data_timeout_ms(mut self, timeout_ms: u32) -> Self {
+        self.data_timeout_ms = timeout_ms;
+        self
+    }
+
+    /// Returns the sensor's current [`Health`].
+    #[inline]
+    #[must_use]
+    pub const fn health(&self) -> Health {
+        self.health
+    }
+
+    /// Returns the [`SensorStatus`] most recently read from the sensor, if
+    /// any.
+    #[inline]
+    #[must_use]
+    pub const fn last_status(&self) -> Option<SensorStatus> {
+        self.last_status
+    }
+
+    /// Returns [`RecoveryStats`] counting the recovery actions taken.
+    #[inline]
+    #[must_use]
+    pub const fn stats(&self) -> RecoveryStats {
+        self.stats
+    }
+
+    /// Returns the supervisor to [`Health::Healthy`], allowing measurements to
+    /// be attempted again after the sensor has failed.
+    ///
+    /// The [`RecoveryStats`] are not cleared.
+    pub fn reset(&mut self) {
+        self.health = Health::Healthy;
+        self.retries = 0;
+        self.restarts = 0;
+        self.resets = 0;
+        self.status_unverified = false;
+        self.since_status = 0;
+        self.last_status = None;
+    }
+
+    /// Records a successful measurement.
+    ///
+    /// If the supervisor has recovered from an error in the sensor's status
+    /// register, the number of resets is kept until the status register is
+    /// next read without errors.
+    pub fn record_success(&mut self) {
+        if self.health == Health::Recovering {
+            self.stats.recovered = self.stats.recovered.saturating_add(1);
+        }
+        self.health = Health::Healthy;
+        self.retries = 0;
+        self.restarts = 0;
+        if !self.status_unverified {
+            self.resets = 0;
+        }
+    }
+
+    /// Records a failed measurement, returning the [`RecoveryAction`] to take,
+    /// or [`None`] if every action has been exhausted and the sensor is
+    /// considered to have failed.
+    pub fn record_failure<E>(&mut self, error: &Error<E>) -> Option<RecoveryAction> {
+        if self.health == Health::Failed {
+            return None;
+        }
+        self.stats.failures = self.stats.failures.saturating_add(1);
+        let status_error = matches!(error, Error::DeviceStatus(_));
+        self.status_unverified |= status_error;
+        let action = if !status_error && self.retries < self.max_retries {
+            self.retries += 1;
+            self.stats.retries = self.stats.retries.saturating_add(1);
+            RecoveryAction::Retry
+        } else if !status_error && self.restarts < self.max_restarts {
+            self.retries = 0;
+            self.restarts += 1;
+            self.stats.restarts = self.stats.restarts.saturating_add(1);
+            RecoveryAction::RestartMeasurement
+        } else if self.resets < self.max_resets {
+            self.retries = 0;
+            self.restarts = 0;
+            self.resets += 1;
+            self.stats.resets = self.stats.resets.saturating_add(1);
+            RecoveryAction::Reset
+        } else {
+            self.health = Health::Failed;
+            self.stats.failed = self.stats.failed.saturating_add(1);
+            return None;
+        };
+        self.health = Health::Recovering;
+        Some(action)
+    }
+
+    pub(crate) const fn data_timeout_ms(&self) -> u32 {
+        self.data_timeout_ms
+    }
+
+    /// Counts a measurement towards the next status check, returning `true`
+    /// if the status register should be read.
+    pub(crate) fn status_due(&mut self) -> bool {
+        if self.status_interval == 0 {
+            return false;
+        }
+        self.since_status += 1;
+        if self.since_status >= self.status_interval {
+            self.since_status = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn status_read(&mut self, status: SensorStatus) {
+        self.last_status = Some(status);
+        if !status.intersects(SensorStatus::ERROR) {
+            self.status_unverified = false;
+        }
+    }
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal::i2c::ErrorKind;
+
+    const FAN_ERROR: Error<ErrorKind> = Error::DeviceStatus(SensorStatus::FAN_ERROR);
+
+    /// Simulates a measurement, reading the status register if it is due.
+    fn measure(supervisor: &mut Supervisor, status: SensorStatus) -> Option<RecoveryAction> {
+        if supervisor.status_due() {
+            supervisor.status_read(status);
+            if status.intersects(SensorStatus::ERROR) {
+                return supervisor.record_failure(&Error::<ErrorKind>::DeviceStatus(status));
+            }
+        }
+        supervisor.record_success();
+        None
+    }
+
+    #[test]
+    fn persistent_status_error_fails() {
+        let mut supervisor = Supervisor::new().with_status_interval(3).with_max_resets(2);
+        let mut resets = 0;
+        for _ in 0..100 {
+            match measure(&mut supervisor, SensorStatus::FAN_ERROR) {
+                Some(RecoveryAction::Reset) => resets += 1,
+                Some(action) => panic!("unexpected recovery action {}", action as u8),
+                None => {}
+            }
+            if supervisor.health() == Health::Failed {
+                break;
+            }
+        }
+        assert!(supervisor.health() == Health::Failed);
+        assert_eq!(resets, 2);
+        assert_eq!(supervisor.stats().failed, 1);
+    }
+
+    #[test]
+    fn clean_status_clears_resets() {
+        let mut supervisor = Supervisor::new().with_status_interval(1).with_max_resets(1);
+        for _ in 0..3 {
+            assert!(supervisor.record_failure(&FAN_ERROR) == Some(RecoveryAction::Reset));
+            // Successful measurements alone don't clear the resets...
+            supervisor.record_success();
+            supervisor.record_success();
+            // ...but a clean status read does.
+            assert!(measure(&mut supervisor, SensorStatus::empty()).is_none());
+            assert!(supervisor.health() == Health::Healthy);
+        }
+    }
+
+    #[test]
+    fn other_errors_cleared_by_success() {
+        let mut supervisor = Supervisor::new().with_max_retries(1).with_max_restarts(0);
+        for _ in 0..10 {
+            let error = Error::<ErrorKind>::Timeout;
+            assert!(supervisor.record_failure(&error) == Some(RecoveryAction::Retry));
+            assert!(supervisor.record_failure(&error) == Some(RecoveryAction::Reset));
+            supervisor.record_success();
+        }
+        assert!(supervisor.health() == Health::Healthy);
+    }
+}
@@ -0,0 +1,433 @@
+/// A raw VOC signal from the sensor, in unspecified "ticks".
+///
+/// This is the input to the VOC [gas index algorithm](crate::gas_index), and
+/// is *not* the VOC index reported by
+/// [`Measurements::voc_index`](crate::Measurements::voc_index).
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(transparent)]
+pub struct VocTicks(pub u16);
+
+/// A raw NOx signal from the sensor, in unspecified "ticks".
+///
+/// This is the input to the NOx [gas index algorithm](crate::gas_index), and
+/// is *not* the NOx index reported by
+/// [`Measurements::nox_index`](crate::Measurements::nox_index).
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(transparent)]
+pub struct NoxTicks(pub u16);
+
+/// A temperature in degrees Celsius, as measured by the sensor.
+///
+/// This includes the sensor's on-chip temperature offset compensation.
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(transparent)]
+pub struct Celsius(pub f32);
+
+/// A relative humidity percentage (%RH), as measured by the sensor.
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(transparent)]
+pub struct Percent(pub f32);
+
+/// A raw temperature signal from the sensor, in degrees Celsius.
+///
+/// Unlike [`Celsius`], this does not include any temperature offset
+/// compensation.
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(transparent)]
+pub struct RawCelsius(pub f32);
+
+/// A raw relative humidity signal from the sensor, as a percentage (%RH).
+///
+/// Unlike [`Percent`], this does not include any temperature offset
+/// compensation.
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(transparent)]
+pub struct RawPercent(pub f32);
+
+/// A temperature in degrees Celsius which has been compensated for
+/// self-heating on the host, by a [`Compensator`](crate::Compensator).
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(transparent)]
+pub struct CompensatedCelsius(pub f32);
+
+/// A relative humidity percentage (%RH) which has been re-derived on the host
+/// for a [`CompensatedCelsius`] temperature, by a
+/// [`Compensator`](crate::Compensator).
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(transparent)]
+pub struct CompensatedPercent(pub f32);
+
+/// A fixed-point value, represented as an integer `T` scaled by `SCALE`.
+///
+/// This is the representation used by the sensor on the wire, and allows
+/// reading measurements without performing any floating-point arithmetic. The
+/// real value is `raw / SCALE`.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Fixed<T, const SCALE: u16>(T);
+
+/// A mass concentration, in micrograms per cubic meter (µg/m³).
+#[cfg(feature = "units")]
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[repr(transparent)]
+pub struct MassConcentration(pub f32);
+
+/// The molar volume of an ideal gas, in liters, at 25 °C and 1 atmosphere.
+#[cfg(feature = "units")]
+const MOLAR_VOLUME_L: f32 = 24.45;
+
+macro_rules! newtype_conversions {
+    ($($name:ident($T:ty)),+ $(,)?) => {
+        $(
+            impl $name {
+                #[doc = concat!("Returns the value of this `", stringify!($name), "` as a [`", stringify!($T), "`].")]
+                #[inline]
+                #[must_use]
+                pub const fn get(self) -> $T {
+                    self.0
+                }
+            }
+
+            impl From<$T> for $name {
+                #[inline]
+                fn from(value: $T) -> Self {
+                    Self(value)
+                }
+            }
+
+            impl From<$name> for $T {
+                #[inline]
+                fn from($name(value): $name) -> Self {
+                    value
+                }
+            }
+        )+
+    };
+}
+
+newtype_conversions! {
+    VocTicks(u16),
+    NoxTicks(u16),
+    Celsius(f32),
+    Percent(f32),
+    RawCelsius(f32),
+    RawPercent(f32),
+    CompensatedCelsius(f32),
+    CompensatedPercent(f32),
+}
+
+#[cfg(feature = "units")]
+newtype_conversions! {
+    MassConcentration(f32),
+}
+
+// === impl Fixed ===
+
+impl<T: Copy, const SCALE: u16> Fixed<T, SCALE> {
+    /// The scale factor of this fixed-point type.
+    pub const SCALE: u16 = SCALE;
+
+    const SCALE_IS_NONZERO: () = assert!(SCALE != 0, "the scale of a `Fixed` must not be 0");
+
+    /// Returns a new fixed-point value from a raw, scaled integer.
+    #[inline]
+    #[must_use]
+    pub const fn from_raw(raw: T) -> Self {
+        let () = Self::SCALE_IS_NONZERO;
+        Self(raw)
+    }
+
+    /// Returns the raw, scaled integer representation of this value.
+    #[inline]
+    #[must_use]
+    pub const fn raw(self) -> T {
+        self.0
+    }
+}
+
+impl<const SCALE: u16> Fixed<u16, SCALE> {
+    /// Returns the integer part of this value.
+    #[inline]
+    #[must_use]
+    pub const fn integer(self) -> u16 {
+        self.0 / SCALE
+    }
+
+    /// Returns the value scaled to the provided `scale`, rounding towards zero.
+    ///
+    /// For example, `value.rescale(100)` returns the value in hundredths. The
+    /// result is computed in 64 bits, so it cannot overflow for any `scale`.
+    #[inline]
+    #[must_use]
+    pub const fn rescale(self, scale: u32) -> u64 {
+        self.0 as u64 * scale as u64 / SCALE as u64
+    }
+
+    /// Returns this value as a [`f32`].
+    #[cfg(feature = "float")]
+    #[inline]
+    #[must_use]
+    pub fn to_f32(self) -> f32 {
+        f32::from(self.0) / f32::from(SCALE)
+    }
+}
+
+impl<const SCALE: u16> Fixed<i16, SCALE> {
+    /// Returns the integer part of this value, rounding towards zero.
+    #[inline]
+    #[must_use]
+    pub const fn integer(self) -> i16 {
+        // `SCALE` may not fit in an `i16`, but the quotient always does.
+        (self.0 as i32 / SCALE as i32) as i16
+    }
+
+    /// Returns the value scaled to the provided `scale`, rounding towards zero.
+    ///
+    /// For example, `value.rescale(100)` returns the value in hundredths. The
+    /// result is computed in 64 bits, so it cannot overflow for any `scale`.
+    #[inline]
+    #[must_use]
+    pub const fn rescale(self, scale: i32) -> i64 {
+        self.0 as i64 * scale as i64 / SCALE as i64
+    }
+
+    /// Returns this value as a [`f32`].
+    #[cfg(feature = "float")]
+    #[inline]
+    #[must_use]
+    pub fn to_f32(self) -> f32 {
+        f32::from(self.0) / f32::from(SCALE)
+    }
+}
+
+#[cfg(feature = "fmt")]
+impl<T, const SCALE: u16> core::fmt::Debug for Fixed<T, SCALE>
+where
+    Self: core::fmt::Display,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "fmt")]
+impl<const SCALE: u16> core::fmt::Display for Fixed<u16, SCALE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_fixed(f, false, u32::from(self.0), SCALE)
+    }
+}
+
+#[cfg(feature = "fmt")]
+impl<const SCALE: u16> core::fmt::Display for Fixed<i16, SCALE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_fixed(f, self.0 < 0, self.0.unsigned_abs().into(), SCALE)
+    }
+}
+
+/// Formats a fixed-point value as a decimal number, without using any
+/// floating-point arithmetic.
+#[cfg(feature = "fmt")]
+fn fmt_fixed(
+    f: &mut core::fmt::Formatter<'_>,
+    negative: bool,
+    magnitude: u32,
+    scale: u16,
+) -> core::fmt::Result {
+    let scale = u32::from(scale);
+    // Enough decimal digits to represent every multiple of `1 / scale`.
+    let mut digits = 0;
+    let mut pow: u64 = 1;
+    while pow < u64::from(scale) {
+        pow *= 10;
+        digits += 1;
+    }
+    let sign = if negative { "-" } else { "" };
+    let integer = magnitude / scale;
+    if digits == 0 {
+        return write!(f, "{sign}{integer}");
+    }
+    // Computed in 64 bits, as `pow` may be as large as 100000.
+    let fraction = u64::from(magnitude % scale) * pow / u64::from(scale);
+    write!(f, "{sign}{integer}.{fraction:0digits$}")
+}
+
+macro_rules! temperature_impls {
+    ($($name:ident),+) => {
+        $(
+            #[cfg(feature = "units")]
+            impl $name {
+                /// Returns this temperature in degrees Fahrenheit.
+                #[inline]
+                #[must_use]
+                pub fn to_fahrenheit(self) -> f32 {
+                    self.0 * 9.0 / 5.0 + 32.0
+                }
+
+                /// Returns this temperature in kelvins.
+                #[inline]
+                #[must_use]
+                pub fn to_kelvin(self) -> f32 {
+                    self.0 + 273.15
+                }
+            }
+
+            #[cfg(feature = "fmt")]
+            impl core::fmt::Display for $name {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    self.0.fmt(f)?;
+                    f.write_str(" °C")
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! humidity_impls {
+    ($($name:ident),+) => {
+        $(
+            #[cfg(feature = "units")]
+            impl $name {
+                /// Returns this percentage as a ratio in the range `0.0..=1.0`.
+                #[inline]
+                #[must_use]
+                pub fn to_ratio(self) -> f32 {
+                    self.0 / 100.0
+                }
+            }
+
+            #[cfg(feature = "fmt")]
+            impl core::fmt::Display for $name {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    self.0.fmt(f)?;
+                    f.write_str(" %RH")
+                }
+            }
+        )+
+    };
+}
+
+// === impl Celsius ===
+
+temperature_impls!(Celsius, RawCelsius, CompensatedCelsius);
+
+// === impl Percent ===
+
+humidity_impls!(Percent, RawPercent, CompensatedPercent);
+
+// === impl MassConcentration ===
+
+#[cfg(feature = "units")]
+impl MassConcentration {
+    /// Returns this concentration in micrograms per cubic meter (µg/m³).
+    #[inline]
+    #[must_use]
+    pub fn as_ug_per_m3(self) -> f32 {
+        self.0
+    }
+
+    /// Returns this concentration in milligrams per cubic meter (mg/m³).
+    #[inline]
+    #[must_use]
+    pub fn as_mg_per_m3(self) -> f32 {
+        self.0 / 1000.0
+    }
+
+    /// Converts this concentration of a gas to parts per billion (ppb) by
+    /// volume, given the gas's molar mass in grams per mole.
+    ///
+    /// This assumes standard ambient conditions (25 °C and 1 atmosphere).
+    /// Particulate matter concentrations have no meaningful volumetric
+    /// equivalent.
+    #[inline]
+    #[must_use]
+    pub fn to_ppb(self, molar_mass_g_per_mol: f32) -> f32 {
+        self.0 * MOLAR_VOLUME_L / molar_mass_g_per_mol
+    }
+
+    /// Converts this concentration of a gas to parts per million (ppm) by
+    /// volume, given the gas's molar mass in grams per mole.
+    ///
+    /// See [`to_ppb`](Self::to_ppb) for details.
+    #[inline]
+    #[must_use]
+    pub fn to_ppm(self, molar_mass_g_per_mol: f32) -> f32 {
+        self.to_ppb(molar_mass_g_per_mol) / 1000.0
+    }
+}
+
+#[cfg(all(feature = "units", feature = "fmt"))]
+impl core::fmt::Display for MassConcentration {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt(f)?;
+        f.write_str(" µg/m³")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rescale_does_not_overflow() {
+        let max = Fixed::<u16, 10>::from_raw(u16::MAX);
+        assert_eq!(max.rescale(100), 655_350);
+        assert_eq!(
+            max.rescale(u32::MAX),
+            u64::from(u16::MAX) * u64::from(u32::MAX) / 10
+        );
+
+        let min = Fixed::<i16, 200>::from_raw(i16::MIN);
+        assert_eq!(min.rescale(100), -16_384);
+        assert_eq!(
+            min.rescale(i32::MAX),
+            i64::from(i16::MIN) * i64::from(i32::MAX) / 200
+        );
+        assert_eq!(
+            min.rescale(i32::MIN),
+            i64::from(i16::MIN) * i64::from(i32::MIN) / 200
+        );
+    }
+
+    #[test]
+    fn rescale_rounds_towards_zero() {
+        assert_eq!(Fixed::<u16, 10>::from_raw(129).rescale(1), 12);
+        assert_eq!(Fixed::<i16, 200>::from_raw(-299).rescale(1), -1);
+        assert_eq!(Fixed::<i16, 200>::from_raw(-299).rescale(100), -149);
+    }
+
+    #[test]
+    fn integer_with_large_scale() {
+        assert_eq!(Fixed::<i16, 65535>::from_raw(i16::MIN).integer(), 0);
+        assert_eq!(Fixed::<i16, 32768>::from_raw(-32768).integer(), -1);
+        assert_eq!(Fixed::<i16, 40000>::from_raw(i16::MAX).integer(), 0);
+        assert_eq!(Fixed::<i16, 200>::from_raw(-299).integer(), -1);
+        assert_eq!(Fixed::<u16, 65535>::from_raw(u16::MAX).integer(), 1);
+    }
+
+    #[cfg(feature = "fmt")]
+    #[test]
+    fn display() {
+        extern crate std;
+        use std::string::ToString;
+
+        assert_eq!(Fixed::<u16, 10>::from_raw(123).to_string(), "12.3");
+        assert_eq!(Fixed::<i16, 200>::from_raw(-301).to_string(), "-1.505");
+        assert_eq!(Fixed::<u16, 1>::from_raw(7).to_string(), "7");
+
+        // The fractional digits of large scales do not overflow.
+        assert_eq!(Fixed::<u16, 65535>::from_raw(65534).to_string(), "0.99998");
+        assert_eq!(
+            Fixed::<i16, 50000>::from_raw(-32767).to_string(),
+            "-0.65534"
+        );
+    }
+}
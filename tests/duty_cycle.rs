@@ -0,0 +1,89 @@
+//! Tests running the drivers through complete duty cycles.
+
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay,
+    i2c::{Mock, Transaction},
+};
+use sensor_sen5x::{DutyCycle, Sen5x};
+
+const START_MEASUREMENT: [u8; 2] = [0x00, 0x21];
+const STOP_MEASUREMENT: [u8; 2] = [0x01, 0x04];
+const READ_DATA_READY: [u8; 2] = [0x02, 0x02];
+const READ_MEASUREMENT: [u8; 2] = [0x03, 0xC4];
+const READY: [u8; 3] = [0x00, 0x01, 0xB0];
+
+/// Computes the Sensirion CRC-8 of a data word.
+fn crc8(word: [u8; 2]) -> u8 {
+    let mut crc = 0xFF_u8;
+    for byte in word {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x31
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Encodes a measurement with the provided PM<sub>1.0</sub> reading, and no
+/// other readings.
+fn measurement(pm1_0: u16) -> Vec<u8> {
+    let words: [u16; 8] = [
+        pm1_0, 0xFFFF, 0xFFFF, 0xFFFF, 0x7FFF, 0x7FFF, 0x7FFF, 0x7FFF,
+    ];
+    words
+        .iter()
+        .flat_map(|word| {
+            let bytes = word.to_be_bytes();
+            [bytes[0], bytes[1], crc8(bytes)]
+        })
+        .collect()
+}
+
+/// Expects a burst which starts the sensor and reads a single measurement.
+fn burst(pm1_0: u16) -> [Transaction; 5] {
+    [
+        Transaction::write(0x69, START_MEASUREMENT.to_vec()),
+        Transaction::write(0x69, READ_DATA_READY.to_vec()),
+        Transaction::read(0x69, READY.to_vec()),
+        Transaction::write(0x69, READ_MEASUREMENT.to_vec()),
+        Transaction::read(0x69, measurement(pm1_0)),
+    ]
+}
+
+#[test]
+fn warm_start_between_bursts() {
+    // The sensor sleeps for 58 s between bursts, which is a little under half
+    // the warm start decay time.
+    let cycle = DutyCycle::new(60_000)
+        .with_warmup_ms(1000)
+        .with_warm_start_decay_ms(120_000);
+    let warm_start = (u64::from(u16::MAX) * 62_000 / 120_000) as u16;
+    let warm_start = warm_start.to_be_bytes();
+
+    let mut expected = Vec::new();
+    expected.extend(burst(10));
+    expected.push(Transaction::write(0x69, STOP_MEASUREMENT.to_vec()));
+    expected.push(Transaction::write(
+        0x69,
+        vec![0x60, 0xC6, warm_start[0], warm_start[1], crc8(warm_start)],
+    ));
+    expected.extend(burst(20));
+    let mut mock = Mock::new(&expected);
+    let mut delay = NoopDelay::new();
+    let mut sensor = Sen5x::new(mock.clone());
+
+    let mut measurements = sensor.duty_cycled(cycle, &mut delay);
+    for pm1_0 in [10, 20] {
+        let Some(Ok(measurement)) = measurements.next() else {
+            panic!("a measurement should be read");
+        };
+        assert!(measurement.pm1_0_fixed().map(|pm| pm.raw()) == Some(pm1_0));
+    }
+    assert!(sensor.state().config().warm_start_parameter() == Some(u16::from_be_bytes(warm_start)));
+
+    mock.done();
+}
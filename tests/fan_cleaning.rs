@@ -0,0 +1,34 @@
+//! Tests for fan cleaning with the drivers.
+
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay,
+    i2c::{Mock, Transaction},
+};
+use sensor_sen5x::{Error, Mode, ParticulateMode, Sen5x};
+
+const START_MEASUREMENT_NO_PM: [u8; 2] = [0x00, 0x37];
+
+#[test]
+fn clean_fan_requires_particulates() {
+    let mut mock = Mock::new(&[Transaction::write(0x69, START_MEASUREMENT_NO_PM.to_vec())]);
+    let mut delay = NoopDelay::new();
+    let mut sensor = Sen5x::new(mock.clone());
+
+    // Nothing is sent while the sensor is idle.
+    assert!(matches!(
+        sensor.clean_fan(&mut delay),
+        Err(Error::WrongMode(Mode::Measuring))
+    ));
+
+    // Nor while it measures with the fan stopped.
+    assert!(sensor
+        .start_measurement(ParticulateMode::Disabled, &mut delay)
+        .is_ok());
+    assert!(matches!(
+        sensor.clean_fan(&mut delay),
+        Err(Error::WrongMode(Mode::Measuring))
+    ));
+    assert_eq!(sensor.state().diagnostics().mode_errors, 2);
+
+    mock.done();
+}
@@ -0,0 +1,98 @@
+//! Tests using the drivers with shared I²C bus devices.
+
+use core::cell::RefCell;
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay,
+    i2c::{Mock, Transaction},
+};
+use sensor_sen5x::{Sen5x, State};
+
+const READ_DATA_READY: [u8; 2] = [0x02, 0x02];
+const READY: [u8; 3] = [0x00, 0x01, 0xB0];
+const NOT_READY: [u8; 3] = [0x00, 0x00, 0x81];
+
+/// Expects both sensors to be asked whether data is ready, with only the
+/// second one reporting that it is.
+fn expect_data_ready(first: u8, second: u8) -> Mock {
+    Mock::new(&[
+        Transaction::write(first, READ_DATA_READY.to_vec()),
+        Transaction::read(first, NOT_READY.to_vec()),
+        Transaction::write(second, READ_DATA_READY.to_vec()),
+        Transaction::read(second, READY.to_vec()),
+    ])
+}
+
+#[test]
+fn refcell_device() {
+    let mut mock = expect_data_ready(0x69, 0x6A);
+    let bus = RefCell::new(mock.clone());
+    let mut delay = NoopDelay::new();
+
+    let mut first = Sen5x::new(embedded_hal_bus::i2c::RefCellDevice::new(&bus));
+    let mut second =
+        Sen5x::new(embedded_hal_bus::i2c::RefCellDevice::new(&bus)).with_i2c_address(0x6A);
+    assert!(matches!(first.data_ready(&mut delay), Ok(false)));
+    assert!(matches!(second.data_ready(&mut delay), Ok(true)));
+
+    mock.done();
+}
+
+#[test]
+fn critical_section_device() {
+    let mut mock = expect_data_ready(0x69, 0x6A);
+    let bus = critical_section::Mutex::new(RefCell::new(mock.clone()));
+    let mut delay = NoopDelay::new();
+
+    let mut first = Sen5x::new(embedded_hal_bus::i2c::CriticalSectionDevice::new(&bus));
+    let mut second =
+        Sen5x::new(embedded_hal_bus::i2c::CriticalSectionDevice::new(&bus)).with_i2c_address(0x6A);
+    assert!(matches!(first.data_ready(&mut delay), Ok(false)));
+    assert!(matches!(second.data_ready(&mut delay), Ok(true)));
+
+    mock.done();
+}
+
+#[test]
+fn borrowed_bus_and_state() {
+    let mut mock = expect_data_ready(0x69, 0x6A);
+    let mut bus = mock.clone();
+    let mut delay = NoopDelay::new();
+
+    // The state of each sensor is stored separately from the bus, and a
+    // driver is constructed for each operation.
+    let mut first = State::new();
+    let mut second = State::new().with_i2c_address(0x6A);
+    assert!(matches!(
+        Sen5x::from_parts(&mut bus, &mut first).data_ready(&mut delay),
+        Ok(false)
+    ));
+    assert!(matches!(
+        Sen5x::from_parts(&mut bus, &mut second).data_ready(&mut delay),
+        Ok(true)
+    ));
+    assert_eq!(first.diagnostics().successful_reads, 1);
+    assert_eq!(second.diagnostics().successful_reads, 1);
+
+    mock.done();
+}
+
+#[cfg(feature = "embedded-hal-async")]
+#[test]
+fn async_mutex_device() {
+    use embassy_embedded_hal::shared_bus::asynch::i2c::I2cDevice;
+    use embassy_sync::{blocking_mutex::raw::NoopRawMutex, mutex::Mutex};
+    use sensor_sen5x::Sen5xAsync;
+
+    let mut mock = expect_data_ready(0x69, 0x6A);
+    let bus = Mutex::<NoopRawMutex, _>::new(mock.clone());
+    let mut delay = NoopDelay::new();
+
+    let mut first = Sen5xAsync::new(I2cDevice::new(&bus));
+    let mut second = Sen5xAsync::new(I2cDevice::new(&bus)).with_i2c_address(0x6A);
+    embassy_futures::block_on(async {
+        assert!(matches!(first.data_ready(&mut delay).await, Ok(false)));
+        assert!(matches!(second.data_ready(&mut delay).await, Ok(true)));
+    });
+
+    mock.done();
+}
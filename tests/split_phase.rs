@@ -0,0 +1,104 @@
+//! Tests mixing split-phase commands with commands whose execution time is
+//! deferred to the driver's clock.
+
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay,
+    i2c::{Mock, Transaction},
+};
+use sensor_sen5x::{Error, MonotonicCounter, ParticulateMode, Sen5x};
+
+const START_MEASUREMENT: [u8; 2] = [0x00, 0x21];
+const STOP_MEASUREMENT: [u8; 2] = [0x01, 0x04];
+const READ_DATA_READY: [u8; 2] = [0x02, 0x02];
+const READY: [u8; 3] = [0x00, 0x01, 0xB0];
+
+#[test]
+fn begin_while_start_measurement_executes() {
+    let mut mock = Mock::new(&[
+        Transaction::write(0x69, START_MEASUREMENT.to_vec()),
+        Transaction::write(0x69, READ_DATA_READY.to_vec()),
+        Transaction::read(0x69, READY.to_vec()),
+    ]);
+    let clock = MonotonicCounter::new();
+    let mut delay = NoopDelay::new();
+    let mut sensor = Sen5x::new(mock.clone()).with_clock(&clock);
+
+    assert!(sensor
+        .start_measurement(ParticulateMode::Enabled, &mut delay)
+        .is_ok());
+    // The start measurement command takes 50 ms to execute, so nothing is
+    // written until it has.
+    clock.advance_ms(49);
+    assert!(matches!(sensor.begin_data_ready(), Err(Error::Busy)));
+
+    clock.advance_ms(1);
+    let Ok(pending) = sensor.begin_data_ready() else {
+        panic!("the data-ready command should be sent");
+    };
+    assert_eq!(pending.ready_at().as_millis(), 70);
+    // Finishing the command early reads nothing.
+    clock.advance_ms(19);
+    assert!(matches!(sensor.finish_command(&pending), Err(Error::Busy)));
+    clock.advance_ms(1);
+    assert!(pending.is_ready(&clock));
+    assert!(matches!(sensor.finish_command(&pending), Ok(true)));
+
+    mock.done();
+}
+
+#[test]
+fn finish_keeps_later_deadline() {
+    let mut mock = Mock::new(&[
+        Transaction::write(0x69, READ_DATA_READY.to_vec()),
+        Transaction::write(0x69, STOP_MEASUREMENT.to_vec()),
+        Transaction::read(0x69, READY.to_vec()),
+    ]);
+    let clock = MonotonicCounter::new();
+    let mut delay = NoopDelay::new();
+    let mut sensor = Sen5x::new(mock.clone()).with_clock(&clock);
+
+    let Ok(pending) = sensor.begin_data_ready() else {
+        panic!("the data-ready command should be sent");
+    };
+    clock.advance_ms(20);
+    assert!(sensor.stop_measurement(&mut delay).is_ok());
+    assert!(matches!(sensor.finish_command(&pending), Ok(true)));
+    // The stop measurement command is still executing.
+    assert!(matches!(sensor.begin_data_ready(), Err(Error::Busy)));
+
+    mock.done();
+}
+
+#[cfg(feature = "embedded-hal-async")]
+#[test]
+fn async_begin_while_start_measurement_executes() {
+    use sensor_sen5x::Sen5xAsync;
+
+    let mut mock = Mock::new(&[
+        Transaction::write(0x69, START_MEASUREMENT.to_vec()),
+        Transaction::write(0x69, READ_DATA_READY.to_vec()),
+        Transaction::read(0x69, READY.to_vec()),
+    ]);
+    let clock = MonotonicCounter::new();
+    let mut delay = NoopDelay::new();
+    let mut sensor = Sen5xAsync::new(mock.clone()).with_clock(&clock);
+
+    embassy_futures::block_on(async {
+        assert!(sensor
+            .start_measurement(ParticulateMode::Enabled, &mut delay)
+            .await
+            .is_ok());
+        clock.advance_ms(49);
+        assert!(matches!(sensor.begin_data_ready().await, Err(Error::Busy)));
+
+        clock.advance_ms(1);
+        let Ok(pending) = sensor.begin_data_ready().await else {
+            panic!("the data-ready command should be sent");
+        };
+        clock.advance_ms(20);
+        assert!(pending.is_ready(&clock));
+        assert!(matches!(sensor.finish_command(&pending).await, Ok(true)));
+    });
+
+    mock.done();
+}